@@ -2,8 +2,8 @@ extern crate hound;
 extern crate signal_processing;
 
 use signal_processing::fast_fourier::FastFourier;
+use signal_processing::fourier_transform::FourierTransform;
 use signal_processing::window;
-use std::i32;
 
 fn main() {
 	let mut reader = hound::WavReader::open("input_file.wav").unwrap();
@@ -14,7 +14,8 @@ fn main() {
 	use signal_processing::phase_vocoder;
 	let pitch_shifter = phase_vocoder::PitchShift { pitch_shift_ratio: 1.2 };
 	let window = window::Window::generate::<window::Sine>(512);
-	let signal = phase_vocoder::process_signal::<FastFourier, _>(&signal, spec.sample_rate.into(), 496, &window, pitch_shifter);
+	let transform = FastFourier::new(window.width());
+	let signal = phase_vocoder::process_signal(&transform, &signal, spec.sample_rate.into(), 496, &window, pitch_shifter);
 	let output_samples: Vec<_> = signal.iter().map(|x| *x as i16).collect();
 
 	let mut writer = hound::WavWriter::create("pitch_shifted_file.wav", spec).unwrap();
@@ -1,3 +1,5 @@
+use crate::float::Float;
+use std::iter;
 use std::ops;
 use super::polar::Polar;
 use super::rectangular::Rectangular;
@@ -7,8 +9,51 @@ pub struct Bin<F>(pub F);
 wrapper!(Bin);
 cross_cast!(Bin, Rectangular, Polar);
 
-impl ops::Mul for Bin<Rectangular> {
-	type Output = Bin<Rectangular>;
+macro_rules! assign_operator {
+	($type: ident, $rhs: ty, $trait: ident, $function: ident, $operator: tt) => {
+		impl<S> ops::$trait<$rhs> for Bin<$type<S>> where S: Float {
+			fn $function(&mut self, rhs: $rhs) {
+				*self = *self $operator rhs;
+			}
+		}
+	};
+}
+
+impl<S> ops::Add for Bin<Rectangular<S>> where S: Float {
+	type Output = Bin<Rectangular<S>>;
+
+	fn add(self, rhs: Self) -> Self::Output {
+		Self(Rectangular {
+			cosine: self.cosine + rhs.cosine,
+			sine: self.sine + rhs.sine,
+		})
+	}
+}
+
+impl<S> ops::Sub for Bin<Rectangular<S>> where S: Float {
+	type Output = Bin<Rectangular<S>>;
+
+	fn sub(self, rhs: Self) -> Self::Output {
+		Self(Rectangular {
+			cosine: self.cosine - rhs.cosine,
+			sine: self.sine - rhs.sine,
+		})
+	}
+}
+
+impl<S> ops::Neg for Bin<Rectangular<S>> where S: Float {
+	type Output = Bin<Rectangular<S>>;
+
+	fn neg(self) -> Self::Output {
+		Self(Rectangular {
+			cosine: -self.cosine,
+			sine: -self.sine,
+		})
+	}
+}
+
+impl<S> ops::Mul for Bin<Rectangular<S>> where S: Float {
+	type Output = Bin<Rectangular<S>>;
 
 	fn mul(self, rhs: Self) -> Self::Output {
 		Self(Rectangular {
@@ -18,8 +63,19 @@ impl ops::Mul for Bin<Rectangular> {
 	}
 }
 
-impl ops::Div for Bin<Rectangular> {
-	type Output = Bin<Rectangular>;
+impl<S> ops::Mul<S> for Bin<Rectangular<S>> where S: Float {
+	type Output = Bin<Rectangular<S>>;
+
+	fn mul(self, rhs: S) -> Self::Output {
+		Self(Rectangular {
+			cosine: self.cosine * rhs,
+			sine: self.sine * rhs,
+		})
+	}
+}
+
+impl<S> ops::Div for Bin<Rectangular<S>> where S: Float {
+	type Output = Bin<Rectangular<S>>;
 
 	fn div(self, rhs: Self) -> Self::Output {
 		let denominator = rhs.cosine * rhs.cosine + rhs.sine * rhs.sine;
@@ -29,8 +85,60 @@ impl ops::Div for Bin<Rectangular> {
 	}
 }
 
-impl ops::Mul for Bin<Polar> {
-	type Output = Bin<Polar>;
+impl<S> ops::Div<S> for Bin<Rectangular<S>> where S: Float {
+	type Output = Bin<Rectangular<S>>;
+
+	fn div(self, rhs: S) -> Self::Output {
+		Self(Rectangular {
+			cosine: self.cosine / rhs,
+			sine: self.sine / rhs,
+		})
+	}
+}
+
+impl<S> iter::Sum for Bin<Rectangular<S>> where S: Float {
+	fn sum<I>(iter: I) -> Self where I: Iterator<Item=Self> {
+		iter.fold(Bin(Rectangular::default()), |sum, bin| sum + bin)
+	}
+}
+
+assign_operator!(Rectangular, Bin<Rectangular<S>>, AddAssign, add_assign, +);
+assign_operator!(Rectangular, Bin<Rectangular<S>>, SubAssign, sub_assign, -);
+assign_operator!(Rectangular, Bin<Rectangular<S>>, MulAssign, mul_assign, *);
+assign_operator!(Rectangular, Bin<Rectangular<S>>, DivAssign, div_assign, /);
+assign_operator!(Rectangular, S, MulAssign, mul_assign, *);
+assign_operator!(Rectangular, S, DivAssign, div_assign, /);
+
+/// Addition and subtraction are performed in rectangular form
+impl<S> ops::Add for Bin<Polar<S>> where S: Float {
+	type Output = Bin<Polar<S>>;
+
+	fn add(self, rhs: Self) -> Self::Output {
+		(Bin::<Rectangular<S>>::from(self) + rhs.into()).into()
+	}
+}
+
+impl<S> ops::Sub for Bin<Polar<S>> where S: Float {
+	type Output = Bin<Polar<S>>;
+
+	fn sub(self, rhs: Self) -> Self::Output {
+		(Bin::<Rectangular<S>>::from(self) - rhs.into()).into()
+	}
+}
+
+impl<S> ops::Neg for Bin<Polar<S>> where S: Float {
+	type Output = Bin<Polar<S>>;
+
+	fn neg(self) -> Self::Output {
+		Self(Polar {
+			magnitude: self.magnitude,
+			phase: self.phase + S::PI(),
+		})
+	}
+}
+
+impl<S> ops::Mul for Bin<Polar<S>> where S: Float {
+	type Output = Bin<Polar<S>>;
 
 	fn mul(self, rhs: Self) -> Self::Output {
 		Self(Polar {
@@ -40,8 +148,19 @@ impl ops::Mul for Bin<Polar> {
 	}
 }
 
-impl ops::Div for Bin<Polar> {
-	type Output = Bin<Polar>;
+impl<S> ops::Mul<S> for Bin<Polar<S>> where S: Float {
+	type Output = Bin<Polar<S>>;
+
+	fn mul(self, rhs: S) -> Self::Output {
+		Self(Polar {
+			magnitude: self.magnitude * rhs,
+			phase: self.phase,
+		})
+	}
+}
+
+impl<S> ops::Div for Bin<Polar<S>> where S: Float {
+	type Output = Bin<Polar<S>>;
 
 	fn div(self, rhs: Self) -> Self::Output {
 		Self(Polar {
@@ -51,6 +170,44 @@ impl ops::Div for Bin<Polar> {
 	}
 }
 
+impl<S> ops::Div<S> for Bin<Polar<S>> where S: Float {
+	type Output = Bin<Polar<S>>;
+
+	fn div(self, rhs: S) -> Self::Output {
+		Self(Polar {
+			magnitude: self.magnitude / rhs,
+			phase: self.phase,
+		})
+	}
+}
+
+impl<S> iter::Sum for Bin<Polar<S>> where S: Float {
+	fn sum<I>(iter: I) -> Self where I: Iterator<Item=Self> {
+		iter.map(Bin::<Rectangular<S>>::from).sum::<Bin<Rectangular<S>>>().into()
+	}
+}
+
+assign_operator!(Polar, Bin<Polar<S>>, AddAssign, add_assign, +);
+assign_operator!(Polar, Bin<Polar<S>>, SubAssign, sub_assign, -);
+assign_operator!(Polar, Bin<Polar<S>>, MulAssign, mul_assign, *);
+assign_operator!(Polar, Bin<Polar<S>>, DivAssign, div_assign, /);
+assign_operator!(Polar, S, MulAssign, mul_assign, *);
+assign_operator!(Polar, S, DivAssign, div_assign, /);
+
+#[cfg(feature = "fast_fourier")]
+impl<S> From<num_complex::Complex<S>> for Bin<Rectangular<S>> {
+	fn from(other: num_complex::Complex<S>) -> Self {
+		Bin(other.into())
+	}
+}
+
+#[cfg(feature = "fast_fourier")]
+impl<S> From<Bin<Rectangular<S>>> for num_complex::Complex<S> {
+	fn from(other: Bin<Rectangular<S>>) -> Self {
+		other.take().into()
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -82,6 +239,59 @@ mod tests {
 		assert_eq!(polar_rectangular, a * b);
 	}
 
+	#[test]
+	fn test_add_rectangular() {
+		let a = Bin(Rectangular { cosine: 1.0, sine: 2.0 });
+		let b = Bin(Rectangular { cosine: 3.0, sine: 4.0 });
+		assert_eq!(a + b, Bin(Rectangular { cosine: 4.0, sine: 6.0 }));
+		assert_eq!(a - b, Bin(Rectangular { cosine: -2.0, sine: -2.0 }));
+		assert_eq!(-a, Bin(Rectangular { cosine: -1.0, sine: -2.0 }));
+		assert_eq!(vec![a, b, a].into_iter().sum::<Bin<Rectangular>>(), Bin(Rectangular { cosine: 5.0, sine: 8.0 }));
+	}
+
+	#[test]
+	fn test_scale_rectangular() {
+		let a = Bin(Rectangular { cosine: 1.0, sine: 2.0 });
+		assert_eq!(a * 2.0, Bin(Rectangular { cosine: 2.0, sine: 4.0 }));
+		assert_eq!(a / 2.0, Bin(Rectangular { cosine: 0.5, sine: 1.0 }));
+	}
+
+	#[test]
+	fn test_assign_rectangular() {
+		let a = Bin(Rectangular { cosine: 1.0, sine: 2.0 });
+		let b = Bin(Rectangular { cosine: 3.0, sine: 4.0 });
+		let mut c = a;
+		c += b;
+		assert_eq!(c, a + b);
+		c -= b;
+		assert_eq!(c, a);
+		c *= b;
+		assert_eq!(c, a * b);
+		c /= b;
+		assert_eq!(c, a);
+		c *= 3.0;
+		assert_eq!(c, a * 3.0);
+		c /= 3.0;
+		assert_eq!(c, a);
+	}
+
+	#[test]
+	fn test_add_polar() {
+		use crate::math;
+		let a = Bin(Rectangular { cosine: 1.0, sine: 2.0 });
+		let b = Bin(Rectangular { cosine: 3.0, sine: 4.0 });
+		let polar_a: Bin<Polar> = a.into();
+		let polar_b: Bin<Polar> = b.into();
+		let sum: Bin<Rectangular> = (polar_a + polar_b).into();
+		assert_eq!(math::approximate(sum.cosine), 4.0);
+		assert_eq!(math::approximate(sum.sine), 6.0);
+
+		let negative: Bin<Rectangular> = (-polar_a).into();
+		assert_eq!(math::approximate(negative.cosine), -1.0);
+		assert_eq!(math::approximate(negative.sine), -2.0);
+		assert_eq!(polar_a * 2.0, Bin(Polar { magnitude: polar_a.magnitude * 2.0, phase: polar_a.phase }));
+	}
+
 	#[test]
 	fn test_divide_polar() {
 		let a = Bin(Polar { magnitude: 1.0, phase: 2.0 });
@@ -20,6 +20,79 @@ pub fn bin_frequency_range(sample_rate: SampleRate, bin_count: usize, bin_index:
 	(center_frequency - half_width, center_frequency + half_width)
 }
 
+/// Calculates the width of a bin for a complex valued Fourier transform
+/// Every bin of the spectrum is counted in `bin_count`
+pub fn complex_bin_width(sample_rate: SampleRate, bin_count: usize) -> f64 {
+	sample_rate as f64 / bin_count as f64
+}
+
+/// Calculates the central frequency of a bin for a complex valued Fourier transform
+/// Bins in the upper half of the spectrum have negative frequencies
+pub fn signed_bin_frequency(sample_rate: SampleRate, bin_count: usize, bin_index: usize) -> Hertz {
+	assert!(bin_index < bin_count);
+	let width = complex_bin_width(sample_rate, bin_count);
+	if is_negative_frequency(bin_count, bin_index) {
+		-width * (bin_count - bin_index) as f64
+	} else {
+		width * bin_index as f64
+	}
+}
+
+/// Calculates the possibly fractional index of a frequency for a complex valued Fourier transform
+/// Negative frequencies map to negative indices
+pub fn frequency_bin_index(sample_rate: SampleRate, bin_count: usize, frequency: Hertz) -> f64 {
+	frequency / complex_bin_width(sample_rate, bin_count)
+}
+
+/// Finds the bin of a complex valued Fourier transform with the closest central frequency
+pub fn nearest_bin_index(sample_rate: SampleRate, bin_count: usize, frequency: Hertz) -> usize {
+	let bin_index = frequency_bin_index(sample_rate, bin_count, frequency).round() as i64;
+	bin_index.rem_euclid(bin_count as i64) as usize
+}
+
+/// Calculates the spacing between points that evenly divide a frequency range
+/// Both the start and end frequencies are included as points
+pub fn zoom_bin_width(start: Hertz, end: Hertz, point_count: usize) -> f64 {
+	assert!(point_count > 0);
+	match point_count {
+		1 => 0.0,
+		_ => (end - start) / (point_count - 1) as f64,
+	}
+}
+
+/// Calculates the frequency of a point that evenly divides a frequency range
+pub fn zoom_bin_frequency(start: Hertz, end: Hertz, point_count: usize, index: usize) -> Hertz {
+	assert!(index < point_count);
+	start + zoom_bin_width(start, end, point_count) * index as f64
+}
+
+pub fn is_negative_frequency(bin_count: usize, bin_index: usize) -> bool {
+	bin_index >= bin_count.div_ceil(2)
+}
+
+/// Finds the bin with the same frequency but the opposite sign
+pub fn negative_frequency_index(bin_count: usize, bin_index: usize) -> usize {
+	assert!(bin_index < bin_count);
+	(bin_count - bin_index) % bin_count
+}
+
+/// Reorders a complex spectrum from the most negative frequency to the most positive
+/// The zero frequency bin is moved to index `bin_count / 2`
+pub fn fourier_shift<T>(bins: &[T]) -> Vec<T> where T: Clone {
+	let mut bins = bins.to_vec();
+	let shift = bins.len() / 2;
+	bins.rotate_right(shift);
+	bins
+}
+
+/// Reverses `fourier_shift` so that the zero frequency bin is first
+pub fn inverse_fourier_shift<T>(bins: &[T]) -> Vec<T> where T: Clone {
+	let mut bins = bins.to_vec();
+	let shift = bins.len() / 2;
+	bins.rotate_left(shift);
+	bins
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -39,4 +112,41 @@ mod tests {
 		assert_eq!(bin_frequency_range(40000, 32, 0), (-312.5, 312.5));
 		assert_eq!(bin_frequency_range(40000, 32, 2), (937.5, 1562.5));
 	}
+
+	#[test]
+	fn test_signed_bin_frequency() {
+		assert_eq!(complex_bin_width(40000, 64), 625.0);
+		assert_eq!(signed_bin_frequency(40000, 64, 31), 19375.0);
+		assert_eq!(signed_bin_frequency(40000, 64, 32), -20000.0);
+		assert_eq!(signed_bin_frequency(40000, 64, 63), -625.0);
+		assert_eq!(signed_bin_frequency(500, 5, 2), 200.0);
+		assert_eq!(signed_bin_frequency(500, 5, 3), -200.0);
+		assert_eq!(negative_frequency_index(64, 1), 63);
+		assert_eq!(negative_frequency_index(64, 0), 0);
+	}
+
+	#[test]
+	fn test_frequency_bin_index() {
+		assert_eq!(frequency_bin_index(40000, 64, 19375.0), 31.0);
+		assert_eq!(frequency_bin_index(40000, 64, 937.5), 1.5);
+		assert_eq!(nearest_bin_index(40000, 64, 1000.0), 2);
+		assert_eq!(nearest_bin_index(40000, 64, -625.0), 63);
+		assert_eq!(nearest_bin_index(500, 5, -200.0), 3);
+	}
+
+	#[test]
+	fn test_zoom_bin_frequency() {
+		assert_eq!(zoom_bin_width(950.0, 1050.0, 101), 1.0);
+		assert_eq!(zoom_bin_frequency(950.0, 1050.0, 101, 100), 1050.0);
+		assert_eq!(zoom_bin_frequency(950.0, 1050.0, 5, 1), 975.0);
+		assert_eq!(zoom_bin_frequency(950.0, 1050.0, 1, 0), 950.0);
+	}
+
+	#[test]
+	fn test_fourier_shift() {
+		assert_eq!(fourier_shift(&[0, 1, 2, -2, -1]), vec![-2, -1, 0, 1, 2]);
+		assert_eq!(fourier_shift(&[0, 1, 2, -3, -2, -1]), vec![-3, -2, -1, 0, 1, 2]);
+		assert_eq!(inverse_fourier_shift(&fourier_shift(&[0, 1, 2, -2, -1])), vec![0, 1, 2, -2, -1]);
+		assert_eq!(inverse_fourier_shift(&fourier_shift(&[0, 1, 2, -3, -2, -1])), vec![0, 1, 2, -3, -2, -1]);
+	}
 }
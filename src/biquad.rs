@@ -0,0 +1,386 @@
+use crate::float::Float;
+use crate::frequency_response;
+use std::f64::consts;
+use super::{Hertz, Sample, SampleRate};
+
+/// Coefficients normalised so that the leading feedback coefficient is one
+/// `y[n] = b0 x[n] + b1 x[n-1] + b2 x[n-2] - a1 y[n-1] - a2 y[n-2]`
+/// Designs follow the Audio EQ Cookbook where a quality factor of `1 / sqrt(2)` is a Butterworth response
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Coefficients<S = Sample> {
+	pub b0: S,
+	pub b1: S,
+	pub b2: S,
+	pub a1: S,
+	pub a2: S,
+}
+
+struct Design {
+	cosine: f64,
+	alpha: f64,
+}
+
+impl Design {
+	fn new(sample_rate: SampleRate, frequency: Hertz, quality: f64) -> Self {
+		assert!(frequency > 0.0 && frequency < sample_rate as f64 / 2.0);
+		assert!(quality > 0.0);
+		let angular = 2.0 * consts::PI * frequency / sample_rate as f64;
+		Design { cosine: angular.cos(), alpha: angular.sin() / (2.0 * quality) }
+	}
+}
+
+impl<S> Coefficients<S> where S: Float {
+	pub fn new(b0: f64, b1: f64, b2: f64, a0: f64, a1: f64, a2: f64) -> Self {
+		assert!(a0 != 0.0);
+		Coefficients {
+			b0: S::convert(b0 / a0),
+			b1: S::convert(b1 / a0),
+			b2: S::convert(b2 / a0),
+			a1: S::convert(a1 / a0),
+			a2: S::convert(a2 / a0),
+		}
+	}
+
+	/// Passes the signal through unchanged
+	pub fn identity() -> Self {
+		Self::new(1.0, 0.0, 0.0, 1.0, 0.0, 0.0)
+	}
+
+	pub fn low_pass(sample_rate: SampleRate, frequency: Hertz, quality: f64) -> Self {
+		let Design { cosine, alpha } = Design::new(sample_rate, frequency, quality);
+		let b1 = 1.0 - cosine;
+		Self::new(b1 / 2.0, b1, b1 / 2.0, 1.0 + alpha, -2.0 * cosine, 1.0 - alpha)
+	}
+
+	pub fn high_pass(sample_rate: SampleRate, frequency: Hertz, quality: f64) -> Self {
+		let Design { cosine, alpha } = Design::new(sample_rate, frequency, quality);
+		let b1 = -(1.0 + cosine);
+		Self::new(-b1 / 2.0, b1, -b1 / 2.0, 1.0 + alpha, -2.0 * cosine, 1.0 - alpha)
+	}
+
+	/// Band pass with a gain of one at the centre frequency
+	pub fn band_pass(sample_rate: SampleRate, frequency: Hertz, quality: f64) -> Self {
+		let Design { cosine, alpha } = Design::new(sample_rate, frequency, quality);
+		Self::new(alpha, 0.0, -alpha, 1.0 + alpha, -2.0 * cosine, 1.0 - alpha)
+	}
+
+	pub fn notch(sample_rate: SampleRate, frequency: Hertz, quality: f64) -> Self {
+		let Design { cosine, alpha } = Design::new(sample_rate, frequency, quality);
+		Self::new(1.0, -2.0 * cosine, 1.0, 1.0 + alpha, -2.0 * cosine, 1.0 - alpha)
+	}
+
+	/// Passes every frequency with a gain of one while shifting the phase around the frequency
+	pub fn all_pass(sample_rate: SampleRate, frequency: Hertz, quality: f64) -> Self {
+		let Design { cosine, alpha } = Design::new(sample_rate, frequency, quality);
+		Self::new(1.0 - alpha, -2.0 * cosine, 1.0 + alpha, 1.0 + alpha, -2.0 * cosine, 1.0 - alpha)
+	}
+
+	/// Boosts or cuts frequencies around the centre frequency by a gain in decibels
+	pub fn peaking(sample_rate: SampleRate, frequency: Hertz, quality: f64, gain: f64) -> Self {
+		let Design { cosine, alpha } = Design::new(sample_rate, frequency, quality);
+		let amplitude = 10.0_f64.powf(gain / 40.0);
+		Self::new(1.0 + alpha * amplitude, -2.0 * cosine, 1.0 - alpha * amplitude,
+		          1.0 + alpha / amplitude, -2.0 * cosine, 1.0 - alpha / amplitude)
+	}
+
+	/// Boosts or cuts frequencies below the corner frequency by a gain in decibels
+	pub fn low_shelf(sample_rate: SampleRate, frequency: Hertz, quality: f64, gain: f64) -> Self {
+		let Design { cosine, alpha } = Design::new(sample_rate, frequency, quality);
+		let amplitude = 10.0_f64.powf(gain / 40.0);
+		let shelf = 2.0 * amplitude.sqrt() * alpha;
+		let (plus, minus) = (amplitude + 1.0, amplitude - 1.0);
+		Self::new(amplitude * (plus - minus * cosine + shelf),
+		          2.0 * amplitude * (minus - plus * cosine),
+		          amplitude * (plus - minus * cosine - shelf),
+		          plus + minus * cosine + shelf,
+		          -2.0 * (minus + plus * cosine),
+		          plus + minus * cosine - shelf)
+	}
+
+	/// Boosts or cuts frequencies above the corner frequency by a gain in decibels
+	pub fn high_shelf(sample_rate: SampleRate, frequency: Hertz, quality: f64, gain: f64) -> Self {
+		let Design { cosine, alpha } = Design::new(sample_rate, frequency, quality);
+		let amplitude = 10.0_f64.powf(gain / 40.0);
+		let shelf = 2.0 * amplitude.sqrt() * alpha;
+		let (plus, minus) = (amplitude + 1.0, amplitude - 1.0);
+		Self::new(amplitude * (plus + minus * cosine + shelf),
+		          -2.0 * amplitude * (minus + plus * cosine),
+		          amplitude * (plus + minus * cosine - shelf),
+		          plus - minus * cosine + shelf,
+		          2.0 * (minus - plus * cosine),
+		          plus - minus * cosine - shelf)
+	}
+
+	/// Magnitude of the frequency response at a frequency
+	pub fn gain(&self, sample_rate: SampleRate, frequency: Hertz) -> f64 {
+		frequency_response::sections_response(&[*self], sample_rate, &[frequency]).magnitudes()[0].into_f64()
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Form {
+	/// Stores previous inputs and outputs which avoids overflow within the filter
+	DirectOne,
+	/// Stores two intermediate values which has better numerical properties for floats
+	TransposedDirectTwo,
+}
+
+#[derive(Debug, Clone)]
+pub struct Biquad<S = Sample> {
+	coefficients: Coefficients<S>,
+	form: Form,
+	state: [S; 4],
+}
+
+impl<S> Biquad<S> where S: Float {
+	pub fn new(coefficients: Coefficients<S>, form: Form) -> Self {
+		Biquad { coefficients, form, state: [S::zero(); 4] }
+	}
+
+	pub fn coefficients(&self) -> &Coefficients<S> {
+		&self.coefficients
+	}
+
+	/// Replaces the coefficients while keeping the state so that parameters can change smoothly
+	pub fn set_coefficients(&mut self, coefficients: Coefficients<S>) {
+		self.coefficients = coefficients;
+	}
+
+	pub fn reset(&mut self) {
+		self.state = [S::zero(); 4];
+	}
+
+	/// Sets the state as if a constant input had always been applied and returns the steady output
+	pub fn settle(&mut self, input: S) -> S {
+		let Coefficients { b0, b1, b2, a1, a2 } = self.coefficients;
+		let denominator = S::one() + a1 + a2;
+		assert!(denominator != S::zero());
+		let output = input * (b0 + b1 + b2) / denominator;
+		self.state = match self.form {
+			Form::DirectOne => [input, input, output, output],
+			Form::TransposedDirectTwo => [output - b0 * input, b2 * input - a2 * output, S::zero(), S::zero()],
+		};
+		output
+	}
+
+	pub fn process_sample(&mut self, sample: S) -> S {
+		let Coefficients { b0, b1, b2, a1, a2 } = self.coefficients;
+		match self.form {
+			Form::DirectOne => {
+				let [input_1, input_2, output_1, output_2] = self.state;
+				let output = b0 * sample + b1 * input_1 + b2 * input_2 - a1 * output_1 - a2 * output_2;
+				self.state = [sample, input_1, output, output_1];
+				output
+			}
+			Form::TransposedDirectTwo => {
+				let output = b0 * sample + self.state[0];
+				self.state[0] = b1 * sample - a1 * output + self.state[1];
+				self.state[1] = b2 * sample - a2 * output;
+				output
+			}
+		}
+	}
+
+	pub fn process(&mut self, signal: &[S]) -> Vec<S> {
+		signal.iter().map(|sample| self.process_sample(*sample)).collect()
+	}
+
+	pub fn process_in_place(&mut self, signal: &mut [S]) {
+		signal.iter_mut().for_each(|sample| *sample = self.process_sample(*sample));
+	}
+}
+
+/// Second order sections applied one after another
+#[derive(Debug, Clone)]
+pub struct Cascade<S = Sample> {
+	sections: Vec<Biquad<S>>,
+}
+
+impl<S> Cascade<S> where S: Float {
+	pub fn new(sections: &[Coefficients<S>], form: Form) -> Self {
+		Cascade {
+			sections: sections.iter().map(|coefficients| Biquad::new(*coefficients, form)).collect(),
+		}
+	}
+
+	pub fn sections(&self) -> &[Biquad<S>] {
+		&self.sections
+	}
+
+	pub fn sections_mut(&mut self) -> &mut [Biquad<S>] {
+		&mut self.sections
+	}
+
+	pub fn reset(&mut self) {
+		self.sections.iter_mut().for_each(Biquad::reset);
+	}
+
+	/// Sets the state of every section as if a constant input had always been applied
+	pub fn settle(&mut self, input: S) -> S {
+		self.sections.iter_mut().fold(input, |input, section| section.settle(input))
+	}
+
+	pub fn process_sample(&mut self, sample: S) -> S {
+		self.sections.iter_mut().fold(sample, |sample, section| section.process_sample(sample))
+	}
+
+	pub fn process(&mut self, signal: &[S]) -> Vec<S> {
+		signal.iter().map(|sample| self.process_sample(*sample)).collect()
+	}
+
+	pub fn process_in_place(&mut self, signal: &mut [S]) {
+		signal.iter_mut().for_each(|sample| *sample = self.process_sample(*sample));
+	}
+
+	/// Magnitude of the combined frequency response at a frequency
+	pub fn gain(&self, sample_rate: SampleRate, frequency: Hertz) -> f64 {
+		let sections: Vec<_> = self.sections.iter().map(|section| section.coefficients).collect();
+		frequency_response::sections_response(&sections, sample_rate, &[frequency]).magnitudes()[0].into_f64()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::math;
+	use super::*;
+
+	const SAMPLE_RATE: SampleRate = 48000;
+	const QUALITY: f64 = consts::FRAC_1_SQRT_2;
+
+	fn assert_gain(coefficients: &Coefficients, frequency: Hertz, expected: f64) {
+		let gain = coefficients.gain(SAMPLE_RATE, frequency);
+		assert!((gain - expected).abs() < 1e-6, "{} {} {}", frequency, gain, expected);
+	}
+
+	fn from_decibels(gain: f64) -> f64 {
+		10.0_f64.powf(gain / 20.0)
+	}
+
+	#[test]
+	fn test_pass_filters() {
+		let low_pass = Coefficients::low_pass(SAMPLE_RATE, 1000.0, QUALITY);
+		assert_gain(&low_pass, 0.0, 1.0);
+		assert_gain(&low_pass, 1000.0, consts::FRAC_1_SQRT_2);
+		assert_gain(&low_pass, 24000.0, 0.0);
+
+		let high_pass = Coefficients::high_pass(SAMPLE_RATE, 1000.0, QUALITY);
+		assert_gain(&high_pass, 0.0, 0.0);
+		assert_gain(&high_pass, 1000.0, consts::FRAC_1_SQRT_2);
+		assert_gain(&high_pass, 24000.0, 1.0);
+
+		let band_pass = Coefficients::band_pass(SAMPLE_RATE, 1000.0, 2.0);
+		assert_gain(&band_pass, 0.0, 0.0);
+		assert_gain(&band_pass, 1000.0, 1.0);
+		assert_gain(&band_pass, 24000.0, 0.0);
+	}
+
+	#[test]
+	fn test_notch_and_all_pass() {
+		let notch = Coefficients::notch(SAMPLE_RATE, 3000.0, 5.0);
+		assert_gain(&notch, 0.0, 1.0);
+		assert_gain(&notch, 3000.0, 0.0);
+		assert_gain(&notch, 24000.0, 1.0);
+
+		let all_pass = Coefficients::all_pass(SAMPLE_RATE, 3000.0, 0.5);
+		for frequency in [0.0, 100.0, 3000.0, 10000.0, 24000.0].iter() {
+			assert_gain(&all_pass, *frequency, 1.0);
+		}
+
+		let signal: Vec<_> = (0..4000).map(|x| (2.0 * consts::PI * 3000.0 * x as f64 / SAMPLE_RATE as f64).sin()).collect();
+		let output = Biquad::new(notch, Form::TransposedDirectTwo).process(&signal);
+		output[3000..].iter().for_each(|sample| assert!(sample.abs() < 1e-6));
+	}
+
+	#[test]
+	fn test_equalisers() {
+		let peaking = Coefficients::peaking(SAMPLE_RATE, 2000.0, 1.0, 6.0);
+		assert_gain(&peaking, 0.0, 1.0);
+		assert_gain(&peaking, 2000.0, from_decibels(6.0));
+		assert_gain(&peaking, 24000.0, 1.0);
+
+		let low_shelf = Coefficients::low_shelf(SAMPLE_RATE, 200.0, QUALITY, -12.0);
+		assert_gain(&low_shelf, 0.0, from_decibels(-12.0));
+		assert_gain(&low_shelf, 200.0, from_decibels(-6.0));
+		assert_gain(&low_shelf, 24000.0, 1.0);
+
+		let high_shelf = Coefficients::high_shelf(SAMPLE_RATE, 8000.0, QUALITY, 9.0);
+		assert_gain(&high_shelf, 0.0, 1.0);
+		assert_gain(&high_shelf, 8000.0, from_decibels(4.5));
+		assert_gain(&high_shelf, 24000.0, from_decibels(9.0));
+	}
+
+	#[test]
+	fn test_forms() {
+		let signal = math::test_signal(500);
+		let coefficients = Coefficients::peaking(SAMPLE_RATE, 5000.0, 3.0, -8.0);
+		let direct = Biquad::new(coefficients, Form::DirectOne).process(&signal);
+		let transposed = Biquad::new(coefficients, Form::TransposedDirectTwo).process(&signal);
+		direct.iter().zip(transposed.iter()).for_each(|(a, b)| assert!((a - b).abs() < 1e-9));
+
+		let identity = Biquad::new(Coefficients::identity(), Form::DirectOne).process(&signal);
+		assert_eq!(identity, signal);
+	}
+
+	#[test]
+	fn test_streaming() {
+		let signal = math::test_signal(500);
+		let coefficients = Coefficients::low_pass(SAMPLE_RATE, 3000.0, 2.0);
+		let expected = Biquad::new(coefficients, Form::TransposedDirectTwo).process(&signal);
+
+		let mut biquad = Biquad::new(coefficients, Form::TransposedDirectTwo);
+		let mut output = Vec::new();
+		for chunk in signal.chunks(37) {
+			let mut chunk = chunk.to_vec();
+			biquad.process_in_place(&mut chunk);
+			output.extend(chunk);
+		}
+		assert_eq!(output, expected);
+
+		biquad.reset();
+		assert_eq!(biquad.process(&signal), expected);
+	}
+
+	#[test]
+	fn test_settle() {
+		for form in [Form::DirectOne, Form::TransposedDirectTwo].iter().cloned() {
+			let sections = [
+				Coefficients::low_pass(SAMPLE_RATE, 1000.0, QUALITY),
+				Coefficients::low_shelf(SAMPLE_RATE, 300.0, QUALITY, 6.0),
+			];
+			let mut cascade = Cascade::new(&sections, form);
+			let output = cascade.settle(0.5);
+			assert!((output - 0.5 * cascade.gain(SAMPLE_RATE, 0.0)).abs() < 1e-12);
+			cascade.process(&[0.5; 100]).iter().for_each(|sample| assert!((sample - output).abs() < 1e-12));
+		}
+	}
+
+	#[test]
+	fn test_cascade() {
+		let signal = math::test_signal(500);
+		let sections = [
+			Coefficients::high_pass(SAMPLE_RATE, 80.0, QUALITY),
+			Coefficients::peaking(SAMPLE_RATE, 1000.0, 2.0, 3.0),
+			Coefficients::low_pass(SAMPLE_RATE, 10000.0, QUALITY),
+		];
+
+		let mut expected = signal.clone();
+		for coefficients in sections.iter() {
+			expected = Biquad::new(*coefficients, Form::DirectOne).process(&expected);
+		}
+
+		let mut cascade = Cascade::new(&sections, Form::DirectOne);
+		let output: Vec<_> = signal.iter().map(|sample| cascade.process_sample(*sample)).collect();
+		assert_eq!(output, expected);
+		assert!((cascade.gain(SAMPLE_RATE, 1000.0) - from_decibels(3.0)).abs() < 1e-2);
+	}
+
+	#[test]
+	fn test_single_precision() {
+		let signal: Vec<f32> = math::test_signal(500).into_iter().map(|sample| sample as f32).collect();
+		let coefficients = Coefficients::<f32>::low_pass(SAMPLE_RATE, 3000.0, QUALITY);
+		let output = Biquad::new(coefficients, Form::TransposedDirectTwo).process(&signal);
+		let expected = Biquad::new(Coefficients::low_pass(SAMPLE_RATE, 3000.0, QUALITY), Form::TransposedDirectTwo)
+			.process(&math::test_signal(500));
+		output.iter().zip(expected.iter()).for_each(|(a, b)| assert!((*a as f64 - b).abs() < 1e-4));
+	}
+}
@@ -0,0 +1,273 @@
+use crate::bin::Bin;
+use crate::float::Float;
+use crate::fourier_transform::FourierTransform;
+use crate::rectangular::Rectangular;
+use super::Sample;
+
+/// Kernel spectrum and transform shared by both methods
+/// Blocks are as long as the transform allows which is the next power of two
+/// of at least the block length plus the kernel length minus one
+struct Plan<T, S> {
+	transform: T,
+	kernel_spectrum: Vec<Bin<Rectangular<S>>>,
+	kernel_length: usize,
+	block_length: usize,
+}
+
+impl<T, S> Plan<T, S> where T: FourierTransform<S>, S: Float {
+	fn new(kernel: &[S], block_length: usize) -> Self {
+		assert!(!kernel.is_empty() && block_length > 0);
+		let transform_length = (block_length + kernel.len() - 1).next_power_of_two();
+		let transform = T::new(transform_length);
+		let kernel_spectrum = transform.analysis(kernel);
+		let block_length = transform_length - kernel.len() + 1;
+		Plan { transform, kernel_spectrum, kernel_length: kernel.len(), block_length }
+	}
+
+	/// Circular convolution of a frame with the kernel
+	fn convolve(&self, frame: &[S]) -> Vec<S> {
+		let spectrum: Vec<_> = self.transform.analysis(frame).into_iter()
+			.zip(self.kernel_spectrum.iter()).map(|(bin, kernel)| bin * *kernel).collect();
+		self.transform.synthesis(&spectrum)
+	}
+}
+
+/// Convolves each block separately and adds the overlapping tails
+pub struct OverlapAdd<T, S = Sample> {
+	plan: Plan<T, S>,
+	block: Vec<S>,
+	tail: Vec<S>,
+	has_input: bool,
+}
+
+impl<T, S> OverlapAdd<T, S> where T: FourierTransform<S>, S: Float {
+	pub fn new(kernel: &[S], block_length: usize) -> Self {
+		let plan = Plan::new(kernel, block_length);
+		let tail = vec![S::zero(); plan.kernel_length - 1];
+		OverlapAdd { block: Vec::with_capacity(plan.block_length), plan, tail, has_input: false }
+	}
+
+	pub fn block_length(&self) -> usize {
+		self.plan.block_length
+	}
+
+	pub fn kernel_length(&self) -> usize {
+		self.plan.kernel_length
+	}
+
+	pub fn reset(&mut self) {
+		self.block.clear();
+		self.tail.iter_mut().for_each(|sample| *sample = S::zero());
+		self.has_input = false;
+	}
+
+	/// Returns the output of every block completed by the samples
+	pub fn process(&mut self, samples: &[S]) -> Vec<S> {
+		self.has_input |= !samples.is_empty();
+		let mut output = Vec::new();
+		let mut samples = samples;
+		while !samples.is_empty() {
+			let take = usize::min(self.block_length() - self.block.len(), samples.len());
+			self.block.extend_from_slice(&samples[..take]);
+			samples = &samples[take..];
+			if self.block.len() == self.block_length() {
+				output.extend(self.convolve_block());
+			}
+		}
+		output
+	}
+
+	/// Returns the remaining output including the tail of the kernel and resets the convolver
+	/// Nothing is returned if no input has been processed since the last reset
+	pub fn flush(&mut self) -> Vec<S> {
+		if !self.has_input {
+			return Vec::new();
+		}
+
+		let remaining = self.block.len();
+		let mut output = self.convolve_block();
+		output.extend(self.tail.iter().cloned());
+		output.truncate(remaining + self.kernel_length() - 1);
+		self.reset();
+		output
+	}
+
+	fn convolve_block(&mut self) -> Vec<S> {
+		let mut convolution = self.plan.convolve(&self.block);
+		convolution.truncate(self.block.len() + self.kernel_length() - 1);
+		convolution.iter_mut().zip(self.tail.iter()).for_each(|(sample, tail)| *sample += *tail);
+		let tail_start = self.block.len();
+		self.tail.clear();
+		self.tail.extend_from_slice(&convolution[tail_start..]);
+		self.block.clear();
+		convolution.truncate(tail_start);
+		convolution
+	}
+}
+
+/// Convolves overlapping frames circularly and discards the samples that wrap around
+pub struct OverlapSave<T, S = Sample> {
+	plan: Plan<T, S>,
+	frame: Vec<S>,
+	has_input: bool,
+}
+
+impl<T, S> OverlapSave<T, S> where T: FourierTransform<S>, S: Float {
+	pub fn new(kernel: &[S], block_length: usize) -> Self {
+		let plan = Plan::new(kernel, block_length);
+		OverlapSave { frame: vec![S::zero(); plan.kernel_length - 1], plan, has_input: false }
+	}
+
+	pub fn block_length(&self) -> usize {
+		self.plan.block_length
+	}
+
+	pub fn kernel_length(&self) -> usize {
+		self.plan.kernel_length
+	}
+
+	pub fn reset(&mut self) {
+		self.frame.clear();
+		self.frame.resize(self.kernel_length() - 1, S::zero());
+		self.has_input = false;
+	}
+
+	/// Returns the output of every block completed by the samples
+	pub fn process(&mut self, samples: &[S]) -> Vec<S> {
+		self.has_input |= !samples.is_empty();
+		let mut output = Vec::new();
+		let frame_length = self.plan.transform.signal_length();
+		let mut samples = samples;
+		while !samples.is_empty() {
+			let take = usize::min(frame_length - self.frame.len(), samples.len());
+			self.frame.extend_from_slice(&samples[..take]);
+			samples = &samples[take..];
+			if self.frame.len() == frame_length {
+				output.extend(self.convolve_frame());
+			}
+		}
+		output
+	}
+
+	/// Returns the remaining output including the tail of the kernel and resets the convolver
+	/// Nothing is returned if no input has been processed since the last reset
+	pub fn flush(&mut self) -> Vec<S> {
+		if !self.has_input {
+			return Vec::new();
+		}
+
+		// Buffered input and the tail of the kernel together are the length of the frame
+		let remaining = self.frame.len();
+		let mut output = Vec::new();
+		while output.len() < remaining {
+			let padding = vec![S::zero(); self.plan.transform.signal_length() - self.frame.len()];
+			output.extend(self.process(&padding));
+		}
+		output.truncate(remaining);
+		self.reset();
+		output
+	}
+
+	fn convolve_frame(&mut self) -> Vec<S> {
+		let convolution = self.plan.convolve(&self.frame);
+		let history_start = self.frame.len() - (self.kernel_length() - 1);
+		self.frame.drain(..history_start);
+		convolution[self.kernel_length() - 1..].to_vec()
+	}
+}
+
+#[cfg(feature = "fast_fourier")]
+pub type FastOverlapAdd<S = Sample> = OverlapAdd<crate::fast_fourier::FastFourier<S>, S>;
+#[cfg(feature = "fast_fourier")]
+pub type FastOverlapSave<S = Sample> = OverlapSave<crate::fast_fourier::FastFourier<S>, S>;
+
+#[cfg(test)]
+mod tests {
+	use crate::convolution;
+	use crate::fourier_transform::CorrelationFourier;
+	use crate::math::{self, test_signal};
+	use super::*;
+
+	/// Pushes the signal in chunks of varying lengths
+	fn stream(mut process: impl FnMut(&[f64]) -> Vec<f64>, signal: &[f64]) -> Vec<f64> {
+		let mut output = Vec::new();
+		let mut start = 0;
+		for chunk in [1, 7, 0, 33, 2, 64, 5].iter().cycle() {
+			if start >= signal.len() {
+				break;
+			}
+			let end = usize::min(start + chunk, signal.len());
+			output.extend(process(&signal[start..end]));
+			start = end;
+		}
+		output
+	}
+
+	#[test]
+	fn test_overlap_add() {
+		let signal = test_signal(300);
+		for kernel_length in [1, 5, 40].iter().cloned() {
+			let kernel = test_signal(kernel_length);
+			let expected = convolution::convolve_signal(&signal, &kernel);
+			let mut convolver = OverlapAdd::<CorrelationFourier, _>::new(&kernel, 16);
+			assert_eq!(convolver.block_length() + kernel_length - 1, (16 + kernel_length - 1).next_power_of_two());
+			let mut output = stream(|chunk| convolver.process(chunk), &signal);
+			output.extend(convolver.flush());
+			math::assert_samples(&output, &expected, 1e-9);
+
+			let mut output = convolver.process(&signal);
+			output.extend(convolver.flush());
+			math::assert_samples(&output, &expected, 1e-9);
+		}
+	}
+
+	#[test]
+	fn test_overlap_save() {
+		let signal = test_signal(300);
+		for kernel_length in [1, 5, 40].iter().cloned() {
+			let kernel = test_signal(kernel_length);
+			let expected = convolution::convolve_signal(&signal, &kernel);
+			let mut convolver = OverlapSave::<CorrelationFourier, _>::new(&kernel, 16);
+			let mut output = stream(|chunk| convolver.process(chunk), &signal);
+			output.extend(convolver.flush());
+			math::assert_samples(&output, &expected, 1e-9);
+
+			let mut output = convolver.process(&signal);
+			output.extend(convolver.flush());
+			math::assert_samples(&output, &expected, 1e-9);
+		}
+	}
+
+	#[test]
+	fn test_empty_flush() {
+		let kernel = [1.0, 2.0, 3.0];
+		let mut convolver = OverlapAdd::<CorrelationFourier, _>::new(&kernel, 4);
+		assert!(convolver.flush().is_empty());
+		assert_eq!(convolver.process(&[1.0]), vec![]);
+		assert_eq!(convolver.flush().len(), 3);
+		assert!(convolver.flush().is_empty());
+
+		let mut convolver = OverlapSave::<CorrelationFourier, _>::new(&kernel, 4);
+		assert!(convolver.flush().is_empty());
+		assert_eq!(convolver.process(&[1.0]), vec![]);
+		assert_eq!(convolver.flush().len(), 3);
+		assert!(convolver.flush().is_empty());
+	}
+
+	#[test]
+	#[cfg(feature = "fast_fourier")]
+	fn test_fast_block_convolution() {
+		let signal = test_signal(5000);
+		let kernel = test_signal(129);
+		let expected = convolution::convolve_signal(&signal, &kernel);
+		let mut convolver = FastOverlapAdd::new(&kernel, 256);
+		let mut output = stream(|chunk| convolver.process(chunk), &signal);
+		output.extend(convolver.flush());
+		math::assert_samples(&output, &expected, 1e-9);
+
+		let mut convolver = FastOverlapSave::new(&kernel, 256);
+		let mut output = stream(|chunk| convolver.process(chunk), &signal);
+		output.extend(convolver.flush());
+		math::assert_samples(&output, &expected, 1e-9);
+	}
+}
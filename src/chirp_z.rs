@@ -0,0 +1,149 @@
+use crate::bin::Bin;
+use crate::bin_frequency;
+use crate::float::Float;
+use crate::fourier_transform::ComplexFourierTransform;
+use crate::polar::Polar;
+use crate::rectangular::Rectangular;
+use std::f64::consts;
+use super::{Hertz, Sample, SampleRate};
+
+/// Evaluates the z-transform along a spiral of the complex plane with Bluestein's algorithm
+/// so any complex Fourier transform can be used regardless of the point count
+pub struct ChirpZ<T, S = Sample> {
+	transform: T,
+	signal_length: usize,
+	start: Polar<f64>,
+	step: Polar<f64>,
+	pre_chirp: Vec<Bin<Rectangular<S>>>,
+	post_chirp: Vec<Bin<Rectangular<S>>>,
+	chirp_spectrum: Vec<Bin<Rectangular<S>>>,
+}
+
+impl<T, S> ChirpZ<T, S> where T: ComplexFourierTransform<S>, S: Float {
+	/// Plans a transform that evaluates the points `start * step^-k` for `k` in `0..point_count`
+	pub fn new(signal_length: usize, point_count: usize, start: Polar<f64>, step: Polar<f64>) -> Self {
+		assert!(signal_length > 0 && point_count > 0);
+		let convert = |value: Polar<f64>| {
+			let value = Rectangular::from(value);
+			Bin(Rectangular { cosine: S::convert(value.cosine), sine: S::convert(value.sine) })
+		};
+
+		// Uses nk = (n² + k² - (k - n)²) / 2 to express the transform as a convolution
+		let half_square = |index: usize| (index * index) as f64 / 2.0;
+		let pre_chirp = (0..signal_length).map(|n| {
+			let start = start.powf(-(n as f64));
+			let chirp = step.powf(half_square(n));
+			convert(Polar { magnitude: start.magnitude * chirp.magnitude, phase: start.phase + chirp.phase })
+		}).collect();
+		let post_chirp = (0..point_count).map(|k| convert(step.powf(half_square(k)))).collect();
+
+		let convolution_length = (signal_length + point_count - 1).next_power_of_two();
+		let mut chirp = vec![Rectangular { cosine: S::zero(), sine: S::zero() }; convolution_length];
+		(0..point_count).for_each(|k| chirp[k] = *convert(step.powf(-half_square(k))));
+		(1..signal_length).for_each(|n| chirp[convolution_length - n] = *convert(step.powf(-half_square(n))));
+
+		let transform = T::new(convolution_length);
+		let chirp_spectrum = transform.complex_analysis(&chirp);
+		ChirpZ { transform, signal_length, start, step, pre_chirp, post_chirp, chirp_spectrum }
+	}
+
+	/// Plans a transform that evaluates evenly spaced frequencies on the unit circle
+	/// Both the start and end frequencies are included
+	pub fn frequency_range(signal_length: usize, point_count: usize, sample_rate: SampleRate,
+	                       start: Hertz, end: Hertz) -> Self {
+		let angular = |frequency: Hertz| 2.0 * consts::PI * frequency / sample_rate as f64;
+		let step = bin_frequency::zoom_bin_width(start, end, point_count);
+		let start = Polar { magnitude: 1.0, phase: angular(start) };
+		let step = Polar { magnitude: 1.0, phase: -angular(step) };
+		Self::new(signal_length, point_count, start, step)
+	}
+
+	pub fn signal_length(&self) -> usize {
+		self.signal_length
+	}
+
+	pub fn point_count(&self) -> usize {
+		self.post_chirp.len()
+	}
+
+	/// The point of the complex plane that is evaluated at an index
+	pub fn point(&self, index: usize) -> Polar<f64> {
+		assert!(index < self.point_count());
+		let step = self.step.powf(-(index as f64));
+		Polar { magnitude: self.start.magnitude * step.magnitude, phase: self.start.phase + step.phase }
+	}
+
+	/// Signals shorter than the planned length are padded with zeros
+	pub fn analysis(&self, signal: &[S]) -> Vec<Bin<Rectangular<S>>> {
+		let signal: Vec<_> = signal.iter().map(|sample| Rectangular { cosine: *sample, sine: S::zero() }).collect();
+		self.complex_analysis(&signal)
+	}
+
+	/// Signals shorter than the planned length are padded with zeros
+	pub fn complex_analysis(&self, signal: &[Rectangular<S>]) -> Vec<Bin<Rectangular<S>>> {
+		assert!(signal.len() <= self.signal_length);
+		let signal: Vec<_> = signal.iter().zip(self.pre_chirp.iter())
+			.map(|(sample, chirp)| *(Bin(*sample) * *chirp)).collect();
+		let spectrum: Vec<_> = self.transform.complex_analysis(&signal).into_iter()
+			.zip(self.chirp_spectrum.iter()).map(|(bin, chirp)| bin * *chirp).collect();
+		let convolution = self.transform.complex_synthesis(&spectrum);
+		convolution.into_iter().zip(self.post_chirp.iter())
+			.map(|(sample, chirp)| Bin(sample) * *chirp).collect()
+	}
+}
+
+#[cfg(feature = "fast_fourier")]
+pub type FastChirpZ<S = Sample> = ChirpZ<crate::fast_fourier::FastFourier<S>, S>;
+
+#[cfg(test)]
+mod tests {
+	use crate::fourier_transform::{CorrelationFourier, FourierTransform};
+	use crate::math::{self, test_signal};
+	use super::*;
+
+	#[test]
+	fn test_fourier_points() {
+		let signal = test_signal(12);
+		let step = Polar { magnitude: 1.0, phase: -2.0 * consts::PI / 12.0 };
+		let transform = ChirpZ::<CorrelationFourier, _>::new(12, 12, Polar { magnitude: 1.0, phase: 0.0 }, step);
+		let complex_signal: Vec<_> = signal.iter().map(|x| Rectangular { cosine: *x, sine: 0.0 }).collect();
+		let expected = CorrelationFourier::new(12).complex_analysis(&complex_signal);
+		math::assert_bins(&transform.analysis(&signal), &expected, 1e-9);
+	}
+
+	#[test]
+	fn test_frequency_range() {
+		let signal = test_signal(50);
+		let expected = CorrelationFourier::new(1024).analysis(&signal);
+		let start = bin_frequency::bin_center_frequency(8000, 512, 100);
+		let end = bin_frequency::bin_center_frequency(8000, 512, 140);
+		let transform = ChirpZ::<CorrelationFourier, _>::frequency_range(50, 41, 8000, start, end);
+		math::assert_bins(&transform.analysis(&signal), &expected[100..=140], 1e-9);
+		assert!((transform.point(40).phase - 2.0 * consts::PI * 140.0 / 1024.0).abs() < 1e-12);
+	}
+
+	#[test]
+	fn test_spiral() {
+		let signal = test_signal(9);
+		let start = Polar { magnitude: 1.1, phase: 0.3 };
+		let step = Polar { magnitude: 0.97, phase: 0.2 };
+		let transform = ChirpZ::<CorrelationFourier, _>::new(9, 5, start, step);
+		let expected: Vec<_> = (0..5).map(|k| {
+			let point = transform.point(k);
+			signal.iter().enumerate().map(|(n, x)| {
+				let power = Rectangular::from(point.powf(-(n as f64)));
+				Bin(power) * *x
+			}).sum()
+		}).collect();
+		math::assert_bins(&transform.analysis(&signal), &expected, 1e-9);
+	}
+
+	#[test]
+	#[cfg(feature = "fast_fourier")]
+	fn test_fast_chirp_z() {
+		let signal = test_signal(300);
+		let reference = ChirpZ::<CorrelationFourier, _>::frequency_range(300, 21, 8000, 950.0, 1050.0);
+		let transform = FastChirpZ::frequency_range(300, 21, 8000, 950.0, 1050.0);
+		math::assert_bins(&transform.analysis(&signal), &reference.analysis(&signal), 1e-9);
+	}
+}
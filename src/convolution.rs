@@ -1,39 +1,214 @@
-use crate::fourier_transform::FourierTransform;
-use super::Sample;
+use crate::bin::Bin;
+use crate::block_convolution::OverlapAdd;
+use crate::float::Float;
+use crate::fourier_transform::{self, FourierTransform};
+
+/// Smallest transform used by overlap add relative to the kernel length
+const OVERLAP_ADD_BLOCK_RATIO: usize = 4;
+/// Estimated cost of each butterfly of a fast transform relative to a multiply and add
+const FOURIER_OPERATION_COST: f64 = 2.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConvolutionMode {
+	/// Every sample where the signal and impulse response overlap
+	Full,
+	/// Length of the signal centred on the full convolution
+	Same,
+	/// Only samples where the shorter input completely overlaps the longer one
+	Valid,
+}
+
+/// Stabilises deconvolution where the input spectrum is close to zero
+#[derive(Debug, Clone, PartialEq)]
+pub enum Regularisation<S> {
+	/// Divides the spectra directly which is exact without noise but
+	/// produces infinite values where the input spectrum is zero
+	None,
+	/// Adds a constant to the power of the input spectrum
+	Tikhonov(S),
+	/// Adds the ratio of noise power to signal power at each bin of the real transform
+	Wiener(Vec<S>),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConvolutionMethod {
+	Direct,
+	Fourier,
+	OverlapAdd,
+}
+
+/// Length of the full convolution which is zero if either input is empty
+pub fn convolution_length(signal_length: usize, impulse_length: usize) -> usize {
+	if signal_length == 0 || impulse_length == 0 {
+		return 0;
+	}
+	signal_length + impulse_length - 1
+}
 
 /// Calculates a convolution of a signal
-pub fn convolve_signal(signal: &[Sample], impulse_response: &[Sample]) -> Vec<Sample> {
-	let mut convolution = vec![0.0; signal.len() + impulse_response.len() - 1];
+pub fn convolve_signal<S>(signal: &[S], impulse_response: &[S]) -> Vec<S> where S: Float {
+	let mut convolution = vec![S::zero(); convolution_length(signal.len(), impulse_response.len())];
 	for (sample_index, sample) in signal.iter().enumerate() {
 		for (impulse_index, impulse) in impulse_response.iter().enumerate() {
-			convolution[sample_index + impulse_index] += sample * impulse;
+			convolution[sample_index + impulse_index] += *sample * *impulse;
 		}
 	}
 	convolution
 }
 
 /// Calculates a single sample of the output convolution
-pub fn convolve_single(signal: &[Sample], impulse_response: &[Sample], index: usize) -> Sample {
-	assert!(index < signal.len() + impulse_response.len() - 1);
-	let mut output_sample = 0.0;
+pub fn convolve_single<S>(signal: &[S], impulse_response: &[S], index: usize) -> S where S: Float {
+	assert!(index < convolution_length(signal.len(), impulse_response.len()));
+	let mut output_sample = S::zero();
 	for (impulse_index, impulse) in impulse_response.iter().enumerate() {
 		if impulse_index <= index && (index - impulse_index) < signal.len() {
-			output_sample += signal[index - impulse_index] * impulse;
+			output_sample += signal[index - impulse_index] * *impulse;
 		}
 	}
 	output_sample
 }
 
 /// Calculates a convolution with discrete fourier transforms
-pub fn convolve_fourier<T>(signal: &[Sample], impulse_response: &[Sample]) -> Vec<Sample> where T: FourierTransform {
-	let convolution_length = signal.len() + impulse_response.len() - 1;
-	let signal_bins = T::analysis_extend(&signal, convolution_length);
-	let kernel_bins = T::analysis_extend(&impulse_response, convolution_length);
-
-	let bin_count = (convolution_length + 1) / 2;
-	let output_bins: Vec<_> = (0..bin_count)
-		.map(|index| signal_bins[index] * kernel_bins[index]).collect();
-	T::synthesis(&output_bins, convolution_length)
+/// The transform must be planned for at least the length of the convolution
+pub fn convolve_fourier<S, T>(transform: &T, signal: &[S], impulse_response: &[S])
+                              -> Vec<S> where S: Float, T: FourierTransform<S> {
+	let convolution_length = convolution_length(signal.len(), impulse_response.len());
+	assert!(transform.signal_length() >= convolution_length);
+	let signal_bins = transform.analysis(signal);
+	let kernel_bins = transform.analysis(impulse_response);
+
+	let output_bins: Vec<_> = signal_bins.into_iter().zip(kernel_bins)
+		.map(|(signal_bin, kernel_bin)| signal_bin * kernel_bin).collect();
+	let mut convolution = transform.synthesis(&output_bins);
+	convolution.truncate(convolution_length);
+	convolution
+}
+
+/// Chooses the method with the lowest estimated cost assuming a fast Fourier transform
+pub fn choose_method(signal_length: usize, impulse_length: usize) -> ConvolutionMethod {
+	let (short, long) = (usize::min(signal_length, impulse_length), usize::max(signal_length, impulse_length));
+	if short == 0 {
+		return ConvolutionMethod::Direct;
+	}
+
+	let transform_cost = |length: usize| FOURIER_OPERATION_COST * length as f64 * (length as f64).log2().max(1.0);
+	let direct = (short * long) as f64;
+	let fourier_length = convolution_length(short, long).next_power_of_two();
+	let fourier = 3.0 * transform_cost(fourier_length) + fourier_length as f64;
+	let block_length = overlap_add_transform_length(short) - short + 1;
+	let overlap_add = long.div_ceil(block_length) as f64 * 2.0 * transform_cost(overlap_add_transform_length(short));
+
+	if direct <= fourier && direct <= overlap_add {
+		ConvolutionMethod::Direct
+	} else if fourier <= overlap_add {
+		ConvolutionMethod::Fourier
+	} else {
+		ConvolutionMethod::OverlapAdd
+	}
+}
+
+fn overlap_add_transform_length(impulse_length: usize) -> usize {
+	(OVERLAP_ADD_BLOCK_RATIO * impulse_length).next_power_of_two()
+}
+
+/// Convolves with the method that is estimated to be the fastest
+/// Empty inputs produce an empty output except for `Same` which is all zeros
+pub fn convolve<S, T>(signal: &[S], impulse_response: &[S], mode: ConvolutionMode)
+                      -> Vec<S> where S: Float, T: FourierTransform<S> {
+	let method = choose_method(signal.len(), impulse_response.len());
+	convolve_method::<S, T>(signal, impulse_response, mode, method)
+}
+
+/// Convolves with the given method instead of the estimated fastest which gives the same output
+/// The output of `Same` is the length of the signal rather than the impulse response
+pub fn convolve_method<S, T>(signal: &[S], impulse_response: &[S], mode: ConvolutionMode,
+                             method: ConvolutionMethod) -> Vec<S> where S: Float, T: FourierTransform<S> {
+	if signal.is_empty() || impulse_response.is_empty() {
+		return match mode {
+			ConvolutionMode::Same => vec![S::zero(); signal.len()],
+			_ => Vec::new(),
+		};
+	}
+
+	// Convolution is commutative so the longer input is streamed through overlap add
+	let (short, long) = if impulse_response.len() <= signal.len() {
+		(impulse_response, signal)
+	} else {
+		(signal, impulse_response)
+	};
+
+	let full = match method {
+		ConvolutionMethod::Direct => convolve_signal(long, short),
+		ConvolutionMethod::Fourier => {
+			let transform = T::new(convolution_length(signal.len(), impulse_response.len()).next_power_of_two());
+			convolve_fourier(&transform, signal, impulse_response)
+		}
+		ConvolutionMethod::OverlapAdd => {
+			let block_length = overlap_add_transform_length(short.len()) - short.len() + 1;
+			let mut convolver = OverlapAdd::<T, S>::new(short, block_length);
+			let mut convolution = convolver.process(long);
+			convolution.extend(convolver.flush());
+			convolution
+		}
+	};
+
+	let (start, length) = match mode {
+		ConvolutionMode::Full => (0, full.len()),
+		ConvolutionMode::Same => ((impulse_response.len() - 1) / 2, signal.len()),
+		ConvolutionMode::Valid => (short.len() - 1, long.len() - short.len() + 1),
+	};
+	full[start..start + length].to_vec()
+}
+
+/// Calculates a convolution where samples past the period wrap around to the start
+pub fn circular_convolve_signal<S>(signal: &[S], impulse_response: &[S], period: usize) -> Vec<S> where S: Float {
+	assert!(period > 0);
+	let mut convolution = vec![S::zero(); period];
+	for (sample_index, sample) in signal.iter().enumerate() {
+		for (impulse_index, impulse) in impulse_response.iter().enumerate() {
+			convolution[(sample_index + impulse_index) % period] += *sample * *impulse;
+		}
+	}
+	convolution
+}
+
+/// Calculates a circular convolution with a period of the length of the transform
+pub fn circular_convolve_fourier<S, T>(transform: &T, signal: &[S], impulse_response: &[S])
+                                       -> Vec<S> where S: Float, T: FourierTransform<S> {
+	assert!(signal.len() <= transform.signal_length());
+	assert!(impulse_response.len() <= transform.signal_length());
+	let output_bins: Vec<_> = transform.analysis(signal).into_iter().zip(transform.analysis(impulse_response))
+		.map(|(signal_bin, kernel_bin)| signal_bin * kernel_bin).collect();
+	transform.synthesis(&output_bins)
+}
+
+/// Recovers the impulse response that produced the output from the input
+/// The transform must be planned for at least the length of the output so that
+/// the circular deconvolution is the inverse of a linear convolution
+pub fn deconvolve_fourier<S, T>(transform: &T, output: &[S], input: &[S], impulse_length: usize,
+                                regularisation: &Regularisation<S>) -> Vec<S> where S: Float, T: FourierTransform<S> {
+	assert!(output.len() <= transform.signal_length() && input.len() <= transform.signal_length());
+	assert!(impulse_length <= transform.signal_length());
+	let bin_count = fourier_transform::bin_count(transform.signal_length());
+	if let Regularisation::Wiener(ratios) = regularisation {
+		assert_eq!(ratios.len(), bin_count);
+	}
+
+	let output_bins = transform.analysis(output);
+	let input_bins = transform.analysis(input);
+	let impulse_bins: Vec<_> = output_bins.into_iter().zip(input_bins).enumerate()
+		.map(|(index, (output_bin, input_bin))| {
+			let offset = match regularisation {
+				Regularisation::None => return output_bin / input_bin,
+				Regularisation::Tikhonov(offset) => *offset,
+				Regularisation::Wiener(ratios) => ratios[index],
+			};
+			output_bin * Bin(input_bin.conj()) / (input_bin.norm_sqr() + offset)
+		}).collect();
+
+	let mut impulse_response = transform.synthesis(&impulse_bins);
+	impulse_response.truncate(impulse_length);
+	impulse_response
 }
 
 #[cfg(test)]
@@ -64,17 +239,130 @@ mod tests {
 	fn test_convolve_fourier() {
 		let signal = [0.0, 1.0, 2.0, 3.0, 2.0, 0.0];
 		let impulse_response = [1.0, 2.0];
-		let convolution: Vec<f64> = convolve_fourier::<CorrelationFourier>(&signal, &impulse_response)
+		let transform = CorrelationFourier::new(7);
+		let convolution: Vec<f64> = convolve_fourier(&transform, &signal, &impulse_response)
 			.into_iter().map(math::approximate).collect();
 		assert_eq!(convolution, vec![0.0, 1.0, 2.0 + 2.0, 4.0 + 3.0, 6.0 + 2.0, 4.0, 0.0])
 	}
 
+	#[test]
+	fn test_convolve_fourier_padded() {
+		let signal = [0.0, 1.0, 2.0, 3.0, 2.0, 0.0];
+		let impulse_response = [1.0, 2.0];
+		let transform = CorrelationFourier::new(10);
+		let convolution: Vec<f64> = convolve_fourier(&transform, &signal, &impulse_response)
+			.into_iter().map(math::approximate).collect();
+		assert_eq!(convolution, convolve_signal(&signal, &impulse_response));
+	}
+
+	#[test]
+	fn test_empty_inputs() {
+		let signal = [1.0, 2.0, 3.0];
+		assert!(convolve_signal::<f64>(&signal, &[]).is_empty());
+		assert!(convolve_signal::<f64>(&[], &[]).is_empty());
+		assert!(convolve_fourier(&CorrelationFourier::new(4), &[], &signal).is_empty());
+		for mode in [ConvolutionMode::Full, ConvolutionMode::Valid].iter() {
+			assert!(convolve::<f64, CorrelationFourier>(&signal, &[], *mode).is_empty());
+			assert!(convolve::<f64, CorrelationFourier>(&[], &signal, *mode).is_empty());
+		}
+		assert_eq!(convolve::<f64, CorrelationFourier>(&signal, &[], ConvolutionMode::Same), vec![0.0; 3]);
+		assert!(convolve::<f64, CorrelationFourier>(&[], &signal, ConvolutionMode::Same).is_empty());
+	}
+
+	#[test]
+	fn test_convolution_modes() {
+		let signal = [1.0, 2.0, 3.0, 4.0, 5.0];
+		let impulse_response = [1.0, 0.0, -1.0];
+		let methods = [ConvolutionMethod::Direct, ConvolutionMethod::Fourier, ConvolutionMethod::OverlapAdd];
+		for method in methods.iter().cloned() {
+			let convolve = |signal: &[f64], impulse_response: &[f64], mode| {
+				let convolution = convolve_method::<_, CorrelationFourier>(signal, impulse_response, mode, method);
+				convolution.into_iter().map(math::approximate).collect::<Vec<_>>()
+			};
+
+			assert_eq!(convolve(&signal, &impulse_response, ConvolutionMode::Full), vec![1.0, 2.0, 2.0, 2.0, 2.0, -4.0, -5.0]);
+			assert_eq!(convolve(&signal, &impulse_response, ConvolutionMode::Same), vec![2.0, 2.0, 2.0, 2.0, -4.0]);
+			assert_eq!(convolve(&signal, &impulse_response, ConvolutionMode::Valid), vec![2.0, 2.0, 2.0]);
+			assert_eq!(convolve(&impulse_response, &signal, ConvolutionMode::Same), vec![2.0, 2.0, 2.0]);
+			assert_eq!(convolve(&impulse_response, &signal, ConvolutionMode::Valid), vec![2.0, 2.0, 2.0]);
+			assert_eq!(convolve(&[1.0, 2.0], &[1.0, 1.0, 1.0, 1.0], ConvolutionMode::Same), vec![3.0, 3.0]);
+		}
+	}
+
+	#[test]
+	fn test_choose_method() {
+		assert_eq!(choose_method(1000, 3), ConvolutionMethod::Direct);
+		assert_eq!(choose_method(3, 1000), ConvolutionMethod::Direct);
+		assert_eq!(choose_method(4096, 4096), ConvolutionMethod::Fourier);
+		assert_eq!(choose_method(1 << 20, 256), ConvolutionMethod::OverlapAdd);
+		assert_eq!(choose_method(0, 10), ConvolutionMethod::Direct);
+	}
+
+	#[test]
+	#[cfg(feature = "fast_fourier")]
+	fn test_convolve_fast() {
+		use crate::fast_fourier::FastFourier;
+		let signal = math::test_signal(5000);
+		for kernel_length in [3, 200, 4000].iter().cloned() {
+			let kernel: Vec<_> = (0..kernel_length).map(|x| (x as f64 * 0.1).sin()).collect();
+			let expected = convolve_signal(&signal, &kernel);
+			let convolution = convolve::<_, FastFourier>(&signal, &kernel, ConvolutionMode::Full);
+			math::assert_samples(&convolution, &expected, 1e-8);
+		}
+	}
+
+	#[test]
+	fn test_circular_convolution() {
+		let signal = [1.0, 2.0, 3.0, 4.0];
+		let impulse_response = [1.0, 1.0, 0.0, 2.0];
+		let expected = vec![1.0 + 4.0 + 4.0, 1.0 + 2.0 + 6.0, 2.0 + 3.0 + 8.0, 2.0 + 3.0 + 4.0];
+		assert_eq!(circular_convolve_signal(&signal, &impulse_response, 4), expected);
+		let convolution: Vec<f64> = circular_convolve_fourier(&CorrelationFourier::new(4), &signal, &impulse_response)
+			.into_iter().map(math::approximate).collect();
+		assert_eq!(convolution, expected);
+
+		// A period at least as long as the linear convolution does not wrap
+		let transform = CorrelationFourier::new(7);
+		let convolution: Vec<f64> = circular_convolve_fourier(&transform, &signal, &impulse_response)
+			.into_iter().map(math::approximate).collect();
+		assert_eq!(convolution, convolve_signal(&signal, &impulse_response));
+	}
+
+	#[test]
+	fn test_deconvolution() {
+		let input = math::test_signal(40);
+		let impulse_response = [0.5, -0.25, 0.125, 1.0, 0.0, -0.5];
+		let output = convolve_signal(&input, &impulse_response);
+		let transform = CorrelationFourier::new(64);
+
+		let assert_recovered = |recovered: Vec<f64>, tolerance| math::assert_samples(&recovered, &impulse_response, tolerance);
+
+		assert_recovered(deconvolve_fourier(&transform, &output, &input, 6, &Regularisation::None), 1e-9);
+		assert_recovered(deconvolve_fourier(&transform, &output, &input, 6, &Regularisation::Tikhonov(1e-9)), 1e-6);
+		let tikhonov = deconvolve_fourier(&transform, &output, &input, 6, &Regularisation::Tikhonov(0.5));
+		let wiener = deconvolve_fourier(&transform, &output, &input, 6, &Regularisation::Wiener(vec![0.5; 33]));
+		assert_eq!(tikhonov, wiener);
+	}
+
+	#[test]
+	fn test_regularised_zeros() {
+		// The alternating input has no energy at zero frequency
+		let input = [1.0, -1.0, 1.0, -1.0];
+		let output = convolve_signal(&input, &[1.0, 0.5]);
+		let transform = CorrelationFourier::new(8);
+		let unstable: Vec<f64> = deconvolve_fourier(&transform, &output, &input, 2, &Regularisation::None);
+		assert!(unstable.iter().any(|sample| !sample.is_finite()));
+		let stable: Vec<f64> = deconvolve_fourier(&transform, &output, &input, 2, &Regularisation::Tikhonov(1e-3));
+		assert!(stable.iter().all(|sample| sample.is_finite()));
+	}
+
 	#[bench]
 	#[cfg(feature = "fast_fourier")]
 	fn bench_convolve_fourier(bench: &mut Bencher) {
 		use crate::fast_fourier::FastFourier;
 		let signal: Vec<_> = (0..2048).map(|x| x as f64).collect();
 		let impulse_response: Vec<_> = (0..256).map(|x| x as f64).collect();
-		bench.iter(|| convolve_fourier::<FastFourier>(&signal, &impulse_response));
+		let transform = FastFourier::new(signal.len() + impulse_response.len() - 1);
+		bench.iter(|| convolve_fourier(&transform, &signal, &impulse_response));
 	}
 }
@@ -1,44 +1,46 @@
-use super::fourier_transform;
+use crate::bin::Bin;
+use crate::float::Float;
 use super::fourier_transform::FourierTransform;
-use super::polar::Polar;
-use super::rectangular::Rectangular;
-use super::Sample;
 
-pub fn correlate_signal(signal: &[Sample], target: &[Sample]) -> Vec<Sample> {
+pub fn correlate_signal<S>(signal: &[S], target: &[S]) -> Vec<S> where S: Float {
 	(0..signal.len()).map(|index| correlate_single(signal, target, index)).collect()
 }
 
-pub fn correlate_single(signal: &[Sample], target: &[Sample], index: usize) -> Sample {
+pub fn correlate_single<S>(signal: &[S], target: &[S], index: usize) -> S where S: Float {
 	assert!(index < signal.len());
-	let mut output_sample = 0.0;
+	let mut output_sample = S::zero();
 	for (target_index, target) in target.iter().enumerate() {
 		if index + target_index < signal.len() {
-			output_sample += signal[index + target_index] * target;
+			output_sample += signal[index + target_index] * *target;
 		}
 	}
 	output_sample
 }
 
-pub fn correlation<T>(signal: &[Sample], target: &[Sample]) -> f64 where T: FourierTransform {
-	correlate_fourier::<T>(signal, target).iter().sum()
+pub fn correlation<S, T>(transform: &T, signal: &[S], target: &[S]) -> S where S: Float, T: FourierTransform<S> {
+	correlate_fourier(transform, signal, target).into_iter().sum()
 }
 
-pub fn correlate_fourier<T>(signal: &[Sample], target: &[Sample]) -> Vec<Sample> where T: FourierTransform {
-	let signal_bins = T::analysis(&signal);
-	let target_bins = T::analysis_extend(&target, signal.len());
+/// Correlation wraps around the end of the signal unless the transform
+/// is planned for at least the length of the signal and target combined
+pub fn correlate_fourier<S, T>(transform: &T, signal: &[S], target: &[S])
+                               -> Vec<S> where S: Float, T: FourierTransform<S> {
+	assert!(transform.signal_length() >= signal.len());
+	let signal_bins = transform.analysis(signal);
+	let target_bins = transform.analysis(target);
 
-	let mut output_bins = Vec::new();
-	for index in 0..(fourier_transform::bin_count(signal.len())) {
-		let target_bin: Polar = target_bins[index].take().into();
-		let target_bin: Rectangular = target_bin.complex_conjugate().into();
-		output_bins.push(signal_bins[index] * target_bin.into());
-	}
-	T::synthesis(&output_bins, signal.len())
+	let output_bins: Vec<_> = signal_bins.into_iter().zip(target_bins)
+		.map(|(signal_bin, target_bin)| signal_bin * Bin(target_bin.conj())).collect();
+
+	let mut correlation = transform.synthesis(&output_bins);
+	correlation.truncate(signal.len());
+	correlation
 }
 
 #[cfg(test)]
 mod tests {
 	use crate::fourier_transform::CorrelationFourier;
+	use crate::math;
 	use super::*;
 
 	#[test]
@@ -61,6 +63,17 @@ mod tests {
 	fn test_correlation() {
 		let signal = [0.0, 1.0, 2.0, 5.0, 2.0, 1.0];
 		let target = [1.0, 2.0];
-		assert_eq!(correlation::<CorrelationFourier>(&signal, &target), vec![2.0, 5.0, 12.0, 9.0, 4.0, 1.0].into_iter().sum());
+		let transform = CorrelationFourier::new(signal.len());
+		assert_eq!(correlation(&transform, &signal, &target), vec![2.0, 5.0, 12.0, 9.0, 4.0, 1.0].into_iter().sum());
+	}
+
+	#[test]
+	fn test_correlate_fourier_padded() {
+		let signal = [3.0, 1.0, 2.0, 5.0, 2.0, 1.0];
+		let target = [1.0, 2.0];
+		let transform = CorrelationFourier::new(signal.len() + target.len() - 1);
+		let correlation: Vec<_> = correlate_fourier(&transform, &signal, &target)
+			.into_iter().map(math::approximate).collect();
+		assert_eq!(correlation, correlate_signal(&signal, &target));
 	}
 }
\ No newline at end of file
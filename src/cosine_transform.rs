@@ -0,0 +1,342 @@
+use crate::bin::Bin;
+use crate::float::Float;
+use crate::fourier_transform::ComplexFourierTransform;
+use crate::rectangular::Rectangular;
+use std::f64::consts;
+use std::marker::PhantomData;
+use super::Sample;
+
+/// Discrete cosine and sine transforms of types one to four
+/// Unnormalized transforms follow the same conventions as FFTW
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CosineKind {
+	DctI,
+	DctII,
+	DctIII,
+	DctIV,
+	DstI,
+	DstII,
+	DstIII,
+	DstIV,
+}
+
+impl CosineKind {
+	/// The transform that reverses this transform up to a scale factor
+	pub fn inverse(&self) -> CosineKind {
+		match self {
+			CosineKind::DctII => CosineKind::DctIII,
+			CosineKind::DctIII => CosineKind::DctII,
+			CosineKind::DstII => CosineKind::DstIII,
+			CosineKind::DstIII => CosineKind::DstII,
+			other => *other,
+		}
+	}
+
+	pub fn is_sine(&self) -> bool {
+		matches!(self, CosineKind::DstI | CosineKind::DstII | CosineKind::DstIII | CosineKind::DstIV)
+	}
+
+	/// Offsets of the sample and frequency indices and the half period of the basis functions
+	/// Every basis function is `cos(π (n + sample_offset) (k + frequency_offset) / half_period)`
+	pub fn basis(&self, signal_length: usize) -> (f64, f64, usize) {
+		match self {
+			CosineKind::DctI => (0.0, 0.0, signal_length - 1),
+			CosineKind::DctII => (0.5, 0.0, signal_length),
+			CosineKind::DctIII => (0.0, 0.5, signal_length),
+			CosineKind::DctIV => (0.5, 0.5, signal_length),
+			CosineKind::DstI => (1.0, 1.0, signal_length + 1),
+			CosineKind::DstII => (0.5, 1.0, signal_length),
+			CosineKind::DstIII => (1.0, 0.5, signal_length),
+			CosineKind::DstIV => (0.5, 0.5, signal_length),
+		}
+	}
+
+	/// Samples at the boundaries are only counted once in types one and three
+	pub fn boundary_weight(&self, index: usize, signal_length: usize) -> f64 {
+		let boundary = match self {
+			CosineKind::DctI => index == 0 || index == signal_length - 1,
+			CosineKind::DctIII => index == 0,
+			CosineKind::DstIII => index == signal_length - 1,
+			_ => false,
+		};
+		if boundary { 0.5 } else { 1.0 }
+	}
+
+	/// Factor that the transform followed by its inverse scales the signal by
+	pub fn inverse_scale(&self, signal_length: usize) -> f64 {
+		2.0 * self.basis(signal_length).2 as f64
+	}
+
+	fn orthonormal_input_weight(&self, index: usize, signal_length: usize) -> f64 {
+		if self.boundary_weight(index, signal_length) < 1.0 { consts::SQRT_2 } else { 1.0 }
+	}
+
+	fn orthonormal_output_weight(&self, index: usize, signal_length: usize) -> f64 {
+		let boundary = match self {
+			CosineKind::DctI => index == 0 || index == signal_length - 1,
+			CosineKind::DctII => index == 0,
+			CosineKind::DstII => index == signal_length - 1,
+			_ => false,
+		};
+		let weight = if boundary { consts::FRAC_1_SQRT_2 } else { 1.0 };
+		weight / self.inverse_scale(signal_length).sqrt()
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Normalization {
+	/// Transforms are scaled by `inverse_scale` after being inverted
+	Unnormalized,
+	/// Transforms preserve the energy of the signal and are their own transpose
+	Orthonormal,
+}
+
+/// A cosine or sine transform planned for a single signal length
+pub trait CosineTransform<S = Sample>: Send + Sync where S: Float {
+	fn new(kind: CosineKind, normalization: Normalization, signal_length: usize) -> Self where Self: Sized;
+
+	fn kind(&self) -> CosineKind;
+	fn normalization(&self) -> Normalization;
+	fn signal_length(&self) -> usize;
+
+	/// Calculates an unnormalized transform of a signal with exactly the planned length
+	/// The kind is always the planned kind or its inverse
+	fn unnormalized(&self, kind: CosineKind, signal: &[S]) -> Vec<S>;
+
+	/// Signals shorter than the planned length are padded with zeros
+	fn transform(&self, signal: &[S]) -> Vec<S> {
+		normalized_transform(self, self.kind(), signal)
+	}
+
+	fn inverse(&self, coefficients: &[S]) -> Vec<S> {
+		let kind = self.kind().inverse();
+		let signal = normalized_transform(self, kind, coefficients);
+		match self.normalization() {
+			Normalization::Orthonormal => signal,
+			Normalization::Unnormalized => {
+				let scale = S::convert(kind.inverse_scale(self.signal_length()));
+				signal.into_iter().map(|sample| sample / scale).collect()
+			}
+		}
+	}
+}
+
+fn normalized_transform<S, T>(transform: &T, kind: CosineKind, signal: &[S]) -> Vec<S>
+                              where S: Float, T: CosineTransform<S> + ?Sized {
+	let signal_length = transform.signal_length();
+	assert!(signal.len() <= signal_length);
+	let mut signal = signal.to_vec();
+	signal.resize(signal_length, S::zero());
+	match transform.normalization() {
+		Normalization::Unnormalized => transform.unnormalized(kind, &signal),
+		Normalization::Orthonormal => {
+			signal.iter_mut().enumerate().for_each(|(index, sample)|
+				*sample *= S::convert(kind.orthonormal_input_weight(index, signal_length)));
+			let mut coefficients = transform.unnormalized(kind, &signal);
+			coefficients.iter_mut().enumerate().for_each(|(index, coefficient)|
+				*coefficient *= S::convert(kind.orthonormal_output_weight(index, signal_length)));
+			coefficients
+		}
+	}
+}
+
+fn assert_signal_length(kind: CosineKind, signal_length: usize) {
+	assert!(signal_length > 0);
+	if kind == CosineKind::DctI {
+		assert!(signal_length > 1);
+	}
+}
+
+/// Reference transform that correlates the signal with each basis function
+pub struct CorrelationCosine<S = Sample> {
+	kind: CosineKind,
+	normalization: Normalization,
+	signal_length: usize,
+	_sample: PhantomData<S>,
+}
+
+impl<S> CosineTransform<S> for CorrelationCosine<S> where S: Float {
+	fn new(kind: CosineKind, normalization: Normalization, signal_length: usize) -> Self {
+		assert_signal_length(kind, signal_length);
+		CorrelationCosine { kind, normalization, signal_length, _sample: PhantomData }
+	}
+
+	fn kind(&self) -> CosineKind {
+		self.kind
+	}
+
+	fn normalization(&self) -> Normalization {
+		self.normalization
+	}
+
+	fn signal_length(&self) -> usize {
+		self.signal_length
+	}
+
+	fn unnormalized(&self, kind: CosineKind, signal: &[S]) -> Vec<S> {
+		let signal_length = self.signal_length;
+		let (sample_offset, frequency_offset, half_period) = kind.basis(signal_length);
+		(0..signal_length).map(|k| {
+			signal.iter().enumerate().map(|(n, sample)| {
+				let angle = consts::PI * (n as f64 + sample_offset) * (k as f64 + frequency_offset) / half_period as f64;
+				let basis = if kind.is_sine() { angle.sin() } else { angle.cos() };
+				*sample * S::convert(2.0 * kind.boundary_weight(n, signal_length) * basis)
+			}).sum()
+		}).collect()
+	}
+}
+
+/// Evaluates cosine and sine transforms with a complex Fourier transform
+/// of twice the half period of the basis functions
+pub struct FourierCosine<T, S = Sample> {
+	transform: T,
+	kind: CosineKind,
+	normalization: Normalization,
+	signal_length: usize,
+	_sample: PhantomData<S>,
+}
+
+impl<T, S> CosineTransform<S> for FourierCosine<T, S> where T: ComplexFourierTransform<S>, S: Float {
+	fn new(kind: CosineKind, normalization: Normalization, signal_length: usize) -> Self {
+		assert_signal_length(kind, signal_length);
+		let (_, _, half_period) = kind.basis(signal_length);
+		FourierCosine {
+			transform: T::new(2 * half_period),
+			kind,
+			normalization,
+			signal_length,
+			_sample: PhantomData,
+		}
+	}
+
+	fn kind(&self) -> CosineKind {
+		self.kind
+	}
+
+	fn normalization(&self) -> Normalization {
+		self.normalization
+	}
+
+	fn signal_length(&self) -> usize {
+		self.signal_length
+	}
+
+	fn unnormalized(&self, kind: CosineKind, signal: &[S]) -> Vec<S> {
+		let signal_length = self.signal_length;
+		let (sample_offset, frequency_offset, half_period) = kind.basis(signal_length);
+		debug_assert_eq!(self.transform.signal_length(), 2 * half_period);
+		let half_period = half_period as f64;
+
+		let signal: Vec<_> = signal.iter().enumerate().map(|(n, sample)| {
+			let angle = -consts::PI * n as f64 * frequency_offset / half_period;
+			let weight = S::convert(kind.boundary_weight(n, signal_length));
+			Rectangular {
+				cosine: *sample * weight * S::convert(angle.cos()),
+				sine: *sample * weight * S::convert(angle.sin()),
+			}
+		}).collect();
+
+		let bins = self.transform.complex_analysis(&signal);
+		bins.into_iter().take(signal_length).enumerate().map(|(k, bin)| {
+			let angle = -consts::PI * sample_offset * (k as f64 + frequency_offset) / half_period;
+			let rotation = Bin(Rectangular { cosine: S::convert(angle.cos()), sine: S::convert(angle.sin()) });
+			let bin = bin * rotation * S::convert(2.0);
+			if kind.is_sine() { -bin.sine } else { bin.cosine }
+		}).collect()
+	}
+}
+
+#[cfg(feature = "fast_fourier")]
+pub type FastCosine<S = Sample> = FourierCosine<crate::fast_fourier::FastFourier<S>, S>;
+
+#[cfg(test)]
+mod tests {
+	use crate::fourier_transform::CorrelationFourier;
+	use crate::math::{self, test_signal};
+	use super::*;
+
+	const KINDS: [CosineKind; 8] = [
+		CosineKind::DctI, CosineKind::DctII, CosineKind::DctIII, CosineKind::DctIV,
+		CosineKind::DstI, CosineKind::DstII, CosineKind::DstIII, CosineKind::DstIV,
+	];
+
+	fn assert_coefficients(transform: &impl CosineTransform, signal: &[f64], expected: &[f64]) {
+		math::assert_samples(&transform.transform(signal), expected, 1e-9);
+	}
+
+	#[test]
+	fn test_discrete_cosine_transform() {
+		let signal = [1.0, 2.0, 3.0, 4.0];
+		let transform = CorrelationCosine::new(CosineKind::DctI, Normalization::Unnormalized, 4);
+		assert_coefficients(&transform, &signal, &[15.0, -4.0, 0.0, -1.0]);
+		let transform = CorrelationCosine::new(CosineKind::DctII, Normalization::Unnormalized, 4);
+		assert_coefficients(&transform, &signal, &[20.0, -6.308644059797899, 0.0, -0.4483415291679655]);
+		let transform = CorrelationCosine::new(CosineKind::DctII, Normalization::Orthonormal, 4);
+		assert_coefficients(&transform, &signal, &[5.0, -2.230442497387663, 0.0, -0.15851266778110726]);
+	}
+
+	#[test]
+	fn test_round_trip() {
+		for kind in KINDS.iter().cloned() {
+			for normalization in [Normalization::Unnormalized, Normalization::Orthonormal].iter().cloned() {
+				for signal_length in 2..10 {
+					let signal = test_signal(signal_length);
+					let transform = CorrelationCosine::new(kind, normalization, signal_length);
+					let synthesis = transform.inverse(&transform.transform(&signal));
+					for (sample, expected) in synthesis.iter().zip(signal.iter()) {
+						assert!((sample - expected).abs() < 1e-9, "{:?} {:?} {}", kind, normalization, signal_length);
+					}
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_orthonormal_energy() {
+		for kind in KINDS.iter().cloned() {
+			let signal = test_signal(9);
+			let transform = CorrelationCosine::new(kind, Normalization::Orthonormal, 9);
+			let energy: f64 = signal.iter().map(|x| x * x).sum();
+			let coefficient_energy: f64 = transform.transform(&signal).iter().map(|x| x * x).sum();
+			assert!((energy - coefficient_energy).abs() < 1e-9, "{:?}", kind);
+		}
+	}
+
+	#[test]
+	fn test_fourier_cosine() {
+		for kind in KINDS.iter().cloned() {
+			for normalization in [Normalization::Unnormalized, Normalization::Orthonormal].iter().cloned() {
+				for signal_length in 2..10 {
+					let signal = test_signal(signal_length);
+					let reference = CorrelationCosine::new(kind, normalization, signal_length);
+					let transform = FourierCosine::<CorrelationFourier, _>::new(kind, normalization, signal_length);
+					let expected = reference.transform(&signal);
+					for (coefficient, expected) in transform.transform(&signal).iter().zip(expected.iter()) {
+						assert!((coefficient - expected).abs() < 1e-9, "{:?} {:?} {}", kind, normalization, signal_length);
+					}
+				}
+			}
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "fast_fourier")]
+	fn test_fast_cosine() {
+		for kind in KINDS.iter().cloned() {
+			for signal_length in 2..34 {
+				let signal = test_signal(signal_length);
+				let reference = CorrelationCosine::new(kind, Normalization::Orthonormal, signal_length);
+				let transform = FastCosine::new(kind, Normalization::Orthonormal, signal_length);
+				let coefficients = transform.transform(&signal);
+				for (coefficient, expected) in coefficients.iter().zip(reference.transform(&signal).iter()) {
+					assert!((coefficient - expected).abs() < 1e-9, "{:?} {}", kind, signal_length);
+				}
+
+				let synthesis = transform.inverse(&coefficients);
+				for (sample, expected) in synthesis.iter().zip(signal.iter()) {
+					assert!((sample - expected).abs() < 1e-9, "{:?} {}", kind, signal_length);
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,293 @@
+use crate::float::Float;
+use crate::goertzel::Goertzel;
+use super::{Hertz, Sample, SampleRate};
+
+pub const ROW_FREQUENCIES: [Hertz; 4] = [697.0, 770.0, 852.0, 941.0];
+pub const COLUMN_FREQUENCIES: [Hertz; 4] = [1209.0, 1336.0, 1477.0, 1633.0];
+
+const DIGITS: [[char; 4]; 4] = [
+	['1', '2', '3', 'A'],
+	['4', '5', '6', 'B'],
+	['7', '8', '9', 'C'],
+	['*', '0', '#', 'D'],
+];
+
+/// Returns the row and column frequencies of a digit
+pub fn digit_frequencies(digit: char) -> Option<(Hertz, Hertz)> {
+	DIGITS.iter().enumerate().find_map(|(row, digits)| {
+		let column = digits.iter().position(|other| *other == digit.to_ascii_uppercase())?;
+		Some((ROW_FREQUENCIES[row], COLUMN_FREQUENCIES[column]))
+	})
+}
+
+/// Generates a digit with each of its two tones at the given amplitude
+pub fn generate_digit(digit: char, sample_rate: SampleRate, length: usize, amplitude: f64) -> Option<Vec<Sample>> {
+	let (row, column) = digit_frequencies(digit)?;
+	let angular = |frequency: Hertz| 2.0 * std::f64::consts::PI * frequency / sample_rate as f64;
+	let (row, column) = (angular(row), angular(column));
+	Some((0..length).map(|n| {
+		let n = n as f64;
+		amplitude * ((row * n).sin() + (column * n).sin())
+	}).collect())
+}
+
+/// Generates digits separated by silence
+pub fn generate_sequence(digits: &str, sample_rate: SampleRate, tone_length: usize,
+                         gap_length: usize, amplitude: f64) -> Option<Vec<Sample>> {
+	let mut signal = Vec::new();
+	for digit in digits.chars() {
+		signal.extend(generate_digit(digit, sample_rate, tone_length, amplitude)?);
+		signal.extend(std::iter::repeat_n(0.0, gap_length));
+	}
+	Some(signal)
+}
+
+/// Estimates the squared amplitude of each tone within a frame
+pub fn tone_powers<S>(frame: &[S], frequencies: &[Hertz], sample_rate: SampleRate) -> Vec<f64> where S: Float {
+	let scale = 4.0 / (frame.len() * frame.len()) as f64;
+	frequencies.iter().map(|frequency| {
+		let mut goertzel = Goertzel::new(*frequency, sample_rate);
+		goertzel.push_samples(frame);
+		goertzel.power().into_f64() * scale
+	}).collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct DtmfParameters {
+	pub frame_length: usize,
+	/// Samples between the starts of consecutive frames
+	pub frame_spacing: usize,
+	/// Minimum amplitude of each of the two tones
+	pub minimum_amplitude: f64,
+	/// Minimum fraction of the frame power that is contained in the two tones
+	pub minimum_tone_ratio: f64,
+	/// Maximum decibels that the row tone can be stronger than the column tone
+	pub maximum_normal_twist: f64,
+	/// Maximum decibels that the column tone can be stronger than the row tone
+	pub maximum_reverse_twist: f64,
+	/// Minimum decibels that each tone must exceed the other tones in its group
+	pub minimum_group_margin: f64,
+	/// Consecutive frames that a digit must be detected in
+	pub minimum_tone_frames: usize,
+	/// Frames without a detection that separate repetitions of a digit
+	pub minimum_gap_frames: usize,
+}
+
+impl DtmfParameters {
+	/// Frames of about 25 milliseconds spaced a quarter of a frame apart so that
+	/// digits lasting at least 40 milliseconds always cover two complete frames
+	pub fn new(sample_rate: SampleRate) -> Self {
+		let frame_length = (sample_rate as f64 * 0.0256).round() as usize;
+		DtmfParameters {
+			frame_length,
+			frame_spacing: usize::max(frame_length / 4, 1),
+			minimum_amplitude: 0.01,
+			minimum_tone_ratio: 0.7,
+			maximum_normal_twist: 8.0,
+			maximum_reverse_twist: 4.0,
+			minimum_group_margin: 6.0,
+			minimum_tone_frames: 2,
+			minimum_gap_frames: 1,
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DtmfDigit {
+	pub digit: char,
+	/// Index of the first sample of the first frame the digit was detected in
+	pub start: usize,
+	/// Index after the last sample of the last frame the digit was detected in
+	pub end: usize,
+}
+
+/// Detects a row and a column tone in each frame with Goertzel resonators
+pub struct DtmfDecoder {
+	sample_rate: SampleRate,
+	parameters: DtmfParameters,
+}
+
+impl DtmfDecoder {
+	pub fn new(sample_rate: SampleRate, parameters: DtmfParameters) -> Self {
+		assert!(parameters.frame_length > 0 && parameters.frame_spacing > 0);
+		assert!(parameters.minimum_tone_frames > 0);
+		DtmfDecoder { sample_rate, parameters }
+	}
+
+	/// Detects the digit present in a single frame if it passes validation
+	pub fn detect_frame<S>(&self, frame: &[S]) -> Option<char> where S: Float {
+		let parameters = &self.parameters;
+		let decibels = |ratio: f64| 10.0 * ratio.log10();
+		let strongest = |powers: &[f64]| -> Option<(usize, f64)> {
+			let (index, power) = powers.iter().cloned().enumerate()
+				.max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+			let margin = powers.iter().enumerate().filter(|(other, _)| *other != index)
+				.all(|(_, other)| decibels(power / *other) >= parameters.minimum_group_margin);
+			let amplitude = power >= parameters.minimum_amplitude * parameters.minimum_amplitude;
+			if margin && amplitude { Some((index, power)) } else { None }
+		};
+
+		let (row, row_power) = strongest(&tone_powers(frame, &ROW_FREQUENCIES, self.sample_rate))?;
+		let (column, column_power) = strongest(&tone_powers(frame, &COLUMN_FREQUENCIES, self.sample_rate))?;
+		let twist = decibels(row_power / column_power);
+		if twist > parameters.maximum_normal_twist || -twist > parameters.maximum_reverse_twist {
+			return None;
+		}
+
+		// A sinusoid has a mean power of half its squared amplitude
+		let frame_power: f64 = frame.iter().map(|x| x.into_f64() * x.into_f64()).sum::<f64>() / frame.len() as f64;
+		let tone_power = (row_power + column_power) / 2.0;
+		if tone_power >= parameters.minimum_tone_ratio * frame_power {
+			Some(DIGITS[row][column])
+		} else {
+			None
+		}
+	}
+
+	/// Decodes digits from consecutive frames of a signal
+	/// Samples after the last complete frame are ignored
+	pub fn decode<S>(&self, signal: &[S]) -> Vec<DtmfDigit> where S: Float {
+		let parameters = &self.parameters;
+		let (frame_length, frame_spacing) = (parameters.frame_length, parameters.frame_spacing);
+		let frame_count = (signal.len() + frame_spacing).saturating_sub(frame_length) / frame_spacing;
+		let detections: Vec<_> = (0..frame_count).map(|index| index * frame_spacing)
+			.map(|start| self.detect_frame(&signal[start..start + frame_length])).collect();
+		let frame_end = |end: usize| (end - 1) * frame_spacing + frame_length;
+
+		// Runs of consecutive frames with the same detection
+		let mut runs: Vec<(Option<char>, usize, usize)> = Vec::new();
+		for (index, detection) in detections.into_iter().enumerate() {
+			match runs.last_mut() {
+				Some((last, _, end)) if *last == detection => *end = index + 1,
+				_ => runs.push((detection, index, index + 1)),
+			}
+		}
+
+		let mut digits: Vec<DtmfDigit> = Vec::new();
+		let mut previous_end = None;
+		for (detection, start, end) in runs {
+			let digit = match detection {
+				Some(digit) if end - start >= parameters.minimum_tone_frames => digit,
+				_ => continue,
+			};
+
+			// Short dropouts within the same digit are bridged
+			let bridged = previous_end.is_some_and(|previous_end: usize| start - previous_end < parameters.minimum_gap_frames);
+			match digits.last_mut() {
+				Some(last) if bridged && last.digit == digit => last.end = frame_end(end),
+				_ => digits.push(DtmfDigit { digit, start: start * frame_spacing, end: frame_end(end) }),
+			}
+			previous_end = Some(end);
+		}
+		digits
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn decoder() -> DtmfDecoder {
+		DtmfDecoder::new(8000, DtmfParameters::new(8000))
+	}
+
+	#[test]
+	fn test_digit_frequencies() {
+		assert_eq!(digit_frequencies('5'), Some((770.0, 1336.0)));
+		assert_eq!(digit_frequencies('#'), Some((941.0, 1477.0)));
+		assert_eq!(digit_frequencies('d'), Some((941.0, 1633.0)));
+		assert_eq!(digit_frequencies('E'), None);
+		assert_eq!(generate_sequence("12E", 8000, 10, 10, 1.0), None);
+	}
+
+	#[test]
+	fn test_detect_frame() {
+		let decoder = decoder();
+		for digit in "0123456789*#ABCD".chars() {
+			let frame = generate_digit(digit, 8000, 205, 0.5).unwrap();
+			assert_eq!(decoder.detect_frame(&frame), Some(digit));
+		}
+
+		assert_eq!(decoder.detect_frame(&[0.0; 205]), None);
+		assert_eq!(decoder.detect_frame(&generate_digit('5', 8000, 205, 0.001).unwrap()), None);
+	}
+
+	#[test]
+	fn test_twist() {
+		let decoder = decoder();
+		let row: Vec<_> = (0..205).map(|n| (2.0 * std::f64::consts::PI * 770.0 * n as f64 / 8000.0).sin()).collect();
+		let column: Vec<_> = (0..205).map(|n| (2.0 * std::f64::consts::PI * 1336.0 * n as f64 / 8000.0).sin()).collect();
+		let mix = |row_amplitude: f64, column_amplitude: f64| -> Vec<f64> {
+			row.iter().zip(column.iter()).map(|(r, c)| r * row_amplitude + c * column_amplitude).collect()
+		};
+
+		assert_eq!(decoder.detect_frame(&mix(0.5, 0.3)), Some('5'));
+		assert_eq!(decoder.detect_frame(&mix(0.5, 0.1)), None);
+		assert_eq!(decoder.detect_frame(&mix(0.4, 0.5)), Some('5'));
+		assert_eq!(decoder.detect_frame(&mix(0.2, 0.5)), None);
+		assert_eq!(decoder.detect_frame(&mix(0.5, 0.0)), None);
+	}
+
+	#[test]
+	fn test_noise_rejection() {
+		let decoder = decoder();
+		let mut frame = generate_digit('8', 8000, 205, 0.2).unwrap();
+		frame.iter_mut().enumerate().for_each(|(n, x)| *x += if n % 2 == 0 { 0.5 } else { -0.5 });
+		assert_eq!(decoder.detect_frame(&frame), None);
+	}
+
+	/// Frames overlapping the edges of a tone are detected when most of the frame is the tone
+	fn assert_digits(digits: &[DtmfDigit], expected: &[(char, usize, usize)], tolerance: usize) {
+		assert_eq!(digits.len(), expected.len(), "{:?}", digits);
+		digits.iter().zip(expected).for_each(|(digit, (expected, start, end))| {
+			assert_eq!(digit.digit, *expected);
+			assert!(digit.start.abs_diff(*start) <= tolerance, "{:?}", digit);
+			assert!(digit.end.abs_diff(*end) <= tolerance, "{:?}", digit);
+		});
+	}
+
+	#[test]
+	fn test_decode() {
+		let decoder = decoder();
+		let frame_length = DtmfParameters::new(8000).frame_length;
+		let signal = generate_sequence("1#9", 8000, 4 * frame_length, 3 * frame_length, 0.5).unwrap();
+		assert_digits(&decoder.decode(&signal), &[
+			('1', 0, 4 * frame_length),
+			('#', 7 * frame_length, 11 * frame_length),
+			('9', 14 * frame_length, 18 * frame_length),
+		], frame_length / 2);
+	}
+
+	#[test]
+	fn test_decode_timing() {
+		let decoder = decoder();
+		let frame_length = DtmfParameters::new(8000).frame_length;
+		let mut signal = generate_digit('4', 8000, frame_length / 2, 0.5).unwrap();
+		signal.extend(vec![0.0; 2 * frame_length]);
+		let offset = signal.len();
+		signal.extend(generate_sequence("44", 8000, 3 * frame_length, 2 * frame_length, 0.5).unwrap());
+		assert_digits(&decoder.decode(&signal), &[
+			('4', offset, offset + 3 * frame_length),
+			('4', offset + 5 * frame_length, offset + 8 * frame_length),
+		], frame_length / 2);
+
+		let mut parameters = DtmfParameters::new(8000);
+		parameters.minimum_gap_frames = 3 * frame_length / parameters.frame_spacing;
+		let digits = DtmfDecoder::new(8000, parameters).decode(&signal);
+		assert_digits(&digits, &[('4', offset, offset + 8 * frame_length)], frame_length / 2);
+	}
+
+	#[test]
+	fn test_decode_minimum_duration() {
+		let decoder = decoder();
+		let frame_length = DtmfParameters::new(8000).frame_length;
+		let length = (8000.0 * 0.040) as usize;
+		for offset in (0..frame_length).step_by(17) {
+			let mut signal = vec![0.0; frame_length + offset];
+			signal.extend(generate_digit('7', 8000, length, 0.5).unwrap());
+			signal.extend(vec![0.0; frame_length]);
+			let start = frame_length + offset;
+			assert_digits(&decoder.decode(&signal), &[('7', start, start + length)], frame_length / 2);
+		}
+	}
+}
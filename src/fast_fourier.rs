@@ -1,38 +1,149 @@
 use crate::bin::Bin;
+use crate::float::Float;
 use crate::fourier_transform;
+use crate::fourier_transform::ComplexFourierTransform;
 use crate::fourier_transform::FourierTransform;
 use crate::rectangular::Rectangular;
-use crate::utility;
-use num_complex::Complex64;
-use rustfft::FFTplanner;
+use num_complex::Complex;
+use realfft::ComplexToReal;
+use realfft::RealFftPlanner;
+use realfft::RealToComplex;
+use rustfft::Fft;
+use rustfft::FftNum;
+use rustfft::FftPlanner;
+use std::iter;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::OnceLock;
 use super::Sample;
 
-pub struct FastFourier();
+/// Working memory for a single transform
+/// Buffers are returned to the pool after use so later frames can reuse them
+struct Scratch<S> {
+	signal: Vec<S>,
+	spectrum: Vec<Complex<S>>,
+	scratch: Vec<Complex<S>>,
+}
+
+/// Transforms of complex signals are only planned once they are first used
+struct ComplexPlan<S> {
+	forward: Arc<dyn Fft<S>>,
+	inverse: Arc<dyn Fft<S>>,
+	scratch_pool: Mutex<Vec<Vec<Complex<S>>>>,
+}
 
-impl FourierTransform for FastFourier {
-	fn analysis_extend(signal: &[Sample], signal_length: usize) -> Vec<Bin<Rectangular>> {
-		let upper_bound = fourier_transform::bin_count(signal_length);
-		let mut signal: Vec<_> = signal.iter().map(|real| Complex64::new(*real, 0.0)).collect();
-		utility::pad_default(&mut signal, signal_length);
-		let mut output = vec![Complex64::default(); signal_length];
+/// Transforms real signals directly into the non redundant half of the spectrum
+pub struct FastFourier<S = Sample> {
+	signal_length: usize,
+	forward: Arc<dyn RealToComplex<S>>,
+	inverse: Arc<dyn ComplexToReal<S>>,
+	scratch_pool: Mutex<Vec<Scratch<S>>>,
+	complex_plan: OnceLock<ComplexPlan<S>>,
+}
+
+impl<S> FastFourier<S> where S: Float + FftNum {
+	fn with_scratch<F, R>(&self, function: F) -> R where F: FnOnce(&mut Scratch<S>) -> R {
+		let scratch = self.scratch_pool.lock().unwrap().pop();
+		let mut scratch = scratch.unwrap_or_else(|| {
+			let scratch_length = usize::max(self.forward.get_scratch_len(), self.inverse.get_scratch_len());
+			Scratch {
+				signal: vec![S::zero(); self.signal_length],
+				spectrum: vec![Complex::default(); fourier_transform::bin_count(self.signal_length)],
+				scratch: vec![Complex::default(); scratch_length],
+			}
+		});
 
-		let planner = FFTplanner::new(false).plan_fft(signal_length);
-		planner.process(&mut signal, &mut output);
-		output.into_iter().take(upper_bound)
-		      .map(|complex| Rectangular { cosine: complex.re, sine: complex.im }.into())
-		      .collect()
+		let result = function(&mut scratch);
+		self.scratch_pool.lock().unwrap().push(scratch);
+		result
 	}
 
-	fn synthesis(bins: &[Bin<Rectangular>], signal_length: usize) -> Vec<Sample> {
-		let mirror_bound = fourier_transform::bin_count(signal_length - 1);
-		let mut bins: Vec<_> = bins.iter().map(|complex| Complex64::new(complex.cosine, complex.sine)).collect();
-		utility::pad_default(&mut bins, mirror_bound);
-		(1..mirror_bound).rev().for_each(|index| bins.push(bins[index].conj()));
-		let mut output = vec![Complex64::default(); signal_length];
+	fn complex_process(&self, buffer: &mut [Complex<S>], inverse: bool) {
+		let plan = self.complex_plan.get_or_init(|| {
+			let mut planner = FftPlanner::new();
+			ComplexPlan {
+				forward: planner.plan_fft_forward(self.signal_length),
+				inverse: planner.plan_fft_inverse(self.signal_length),
+				scratch_pool: Mutex::new(Vec::new()),
+			}
+		});
+
+		let transform = if inverse { &plan.inverse } else { &plan.forward };
+		let scratch = plan.scratch_pool.lock().unwrap().pop();
+		let mut scratch = scratch.unwrap_or_else(|| {
+			let scratch_length = usize::max(plan.forward.get_inplace_scratch_len(),
+			                                plan.inverse.get_inplace_scratch_len());
+			vec![Complex::default(); scratch_length]
+		});
 
-		let planner = FFTplanner::new(true).plan_fft(signal_length);
-		planner.process(&mut bins, &mut output);
-		output.into_iter().map(|complex| complex.re / signal_length as f64).collect()
+		transform.process_with_scratch(buffer, &mut scratch);
+		plan.scratch_pool.lock().unwrap().push(scratch);
+	}
+}
+
+impl<S> FourierTransform<S> for FastFourier<S> where S: Float + FftNum {
+	fn new(signal_length: usize) -> Self {
+		assert!(signal_length > 0);
+		let mut planner = RealFftPlanner::new();
+		FastFourier {
+			signal_length,
+			forward: planner.plan_fft_forward(signal_length),
+			inverse: planner.plan_fft_inverse(signal_length),
+			scratch_pool: Mutex::new(Vec::new()),
+			complex_plan: OnceLock::new(),
+		}
+	}
+
+	fn signal_length(&self) -> usize {
+		self.signal_length
+	}
+
+	fn analysis(&self, signal: &[S]) -> Vec<Bin<Rectangular<S>>> {
+		assert!(signal.len() <= self.signal_length);
+		self.with_scratch(|Scratch { signal: buffer, spectrum, scratch }| {
+			let signal = signal.iter().cloned().chain(iter::repeat(S::zero()));
+			buffer.iter_mut().zip(signal).for_each(|(sample, real)| *sample = real);
+			self.forward.process_with_scratch(buffer, spectrum, scratch).unwrap();
+			spectrum.iter().map(|complex| Bin::from(*complex)).collect()
+		})
+	}
+
+	fn synthesis(&self, bins: &[Bin<Rectangular<S>>]) -> Vec<S> {
+		let signal_length = self.signal_length;
+		self.with_scratch(|Scratch { signal, spectrum, scratch }| {
+			let bins = bins.iter().map(|bin| Complex::from(*bin))
+			               .chain(iter::repeat(Complex::default()));
+			spectrum.iter_mut().zip(bins).for_each(|(complex, bin)| *complex = bin);
+
+			// Sine components of the zero and Nyquist frequencies do not contribute to a real signal
+			spectrum[0].im = S::zero();
+			if signal_length.is_multiple_of(2) {
+				spectrum[signal_length / 2].im = S::zero();
+			}
+
+			self.inverse.process_with_scratch(spectrum, signal, scratch).unwrap();
+			let signal_length = S::from_index(signal_length);
+			signal.iter().map(|sample| *sample / signal_length).collect()
+		})
+	}
+}
+
+impl<S> ComplexFourierTransform<S> for FastFourier<S> where S: Float + FftNum {
+	fn complex_analysis(&self, signal: &[Rectangular<S>]) -> Vec<Bin<Rectangular<S>>> {
+		assert!(signal.len() <= self.signal_length);
+		let mut buffer: Vec<_> = signal.iter().map(|sample| Complex::from(*sample)).collect();
+		buffer.resize(self.signal_length, Complex::default());
+		self.complex_process(&mut buffer, false);
+		buffer.into_iter().map(Bin::from).collect()
+	}
+
+	fn complex_synthesis(&self, bins: &[Bin<Rectangular<S>>]) -> Vec<Rectangular<S>> {
+		assert!(bins.len() >= self.signal_length);
+		let mut buffer: Vec<_> = bins.iter().take(self.signal_length)
+		                             .map(|bin| Complex::from(*bin)).collect();
+		self.complex_process(&mut buffer, true);
+		let signal_length = S::from_index(self.signal_length);
+		buffer.into_iter().map(|complex| Rectangular::from(complex / signal_length)).collect()
 	}
 }
 
@@ -51,7 +162,7 @@ mod tests {
 			Bin(Rectangular { cosine: -2.5, sine: 3.4409548 }),
 			Bin(Rectangular { cosine: -2.5, sine: 0.81229924 }),
 		];
-		let synthesis: Vec<_> = FastFourier::synthesis(&bins, 5)
+		let synthesis: Vec<_> = FastFourier::new(5).synthesis(&bins)
 			.into_iter().map(math::approximate).collect();
 		assert_eq!(synthesis, vec![1.0, 2.0, 3.0, 4.0, 5.0]);
 	}
@@ -63,24 +174,115 @@ mod tests {
 			Bin(Rectangular { cosine: -2.0, sine: 2.0 }),
 			Bin(Rectangular { cosine: -2.0, sine: 0.0 }),
 		];
-		let synthesis: Vec<_> = FastFourier::synthesis(&bins, 4);
+		let synthesis: Vec<_> = FastFourier::new(4).synthesis(&bins);
 		assert_eq!(&synthesis, &[1.0, 2.0, 3.0, 4.0]);
 	}
 
 	#[test]
 	fn test_analysis() {
 		let signal = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
-		let bins = FastFourier::analysis(&signal);
-		let synthesis: Vec<_> = FastFourier::synthesis(&bins, 6)
+		let transform = FastFourier::new(6);
+		let bins = transform.analysis(&signal);
+		let synthesis: Vec<_> = transform.synthesis(&bins)
 			.into_iter().map(math::approximate).collect();
 		assert_eq!(synthesis, signal);
 	}
 
+	#[test]
+	fn test_shared_transform() {
+		use rayon::prelude::*;
+		let transform = FastFourier::new(64);
+		let signals: Vec<Vec<_>> = (0..32).map(|offset| (0..64).map(|x| (x + offset) as f64).collect()).collect();
+		let synthesis: Vec<_> = signals.par_iter()
+			.map(|signal| transform.synthesis(&transform.analysis(signal))).collect();
+		for (signal, synthesis) in signals.iter().zip(synthesis) {
+			let synthesis: Vec<_> = synthesis.into_iter().map(|x| x.round()).collect();
+			assert_eq!(&synthesis, signal);
+		}
+	}
+
+	#[test]
+	fn test_analysis_matches_correlation() {
+		use crate::fourier_transform::CorrelationFourier;
+		for signal_length in 1..24 {
+			let signal: Vec<_> = (0..signal_length).map(|x| ((x * 7) % 5) as f64 - 2.0).collect();
+			let expected = CorrelationFourier::new(signal_length).analysis(&signal);
+			let bins = FastFourier::new(signal_length).analysis(&signal);
+			assert_eq!(bins.len(), expected.len());
+			for (bin, expected) in bins.iter().zip(expected.iter()) {
+				assert!((bin.cosine - expected.cosine).abs() < 1e-9);
+				assert!((bin.sine - expected.sine).abs() < 1e-9);
+			}
+		}
+	}
+
+	#[test]
+	fn test_synthesis_matches_correlation() {
+		use crate::fourier_transform::CorrelationFourier;
+		for signal_length in 1..24 {
+			let bins: Vec<_> = (0..fourier_transform::bin_count(signal_length)).map(|x| {
+				Bin(Rectangular { cosine: ((x * 3) % 4) as f64 - 1.5, sine: ((x * 5) % 7) as f64 - 3.0 })
+			}).collect();
+			let expected = CorrelationFourier::new(signal_length).synthesis(&bins);
+			let synthesis = FastFourier::new(signal_length).synthesis(&bins);
+			assert_eq!(synthesis.len(), expected.len());
+			for (sample, expected) in synthesis.iter().zip(expected.iter()) {
+				assert!((sample - expected).abs() < 1e-9);
+			}
+		}
+	}
+
+	fn round_trip_error<S>(signal_length: usize) -> f64 where S: Float + FftNum {
+		let signal: Vec<S> = (0..signal_length).map(|x| {
+			S::convert((x as f64 * 0.05).sin() * 0.8 + (x as f64 * 0.31).cos() * 0.1)
+		}).collect();
+		let transform = FastFourier::new(signal_length);
+		let synthesis = transform.synthesis(&transform.analysis(&signal));
+		signal.iter().zip(synthesis)
+		      .map(|(sample, synthesis)| (sample.into_f64() - synthesis.into_f64()).abs())
+		      .fold(0.0, f64::max)
+	}
+
+	#[test]
+	fn test_single_precision() {
+		let single = round_trip_error::<f32>(8192);
+		let double = round_trip_error::<f64>(8192);
+		assert!(single < 1e-5);
+		assert!(double < 1e-13);
+		assert!(single > double * 1e6);
+	}
+
+	#[test]
+	fn test_complex_matches_correlation() {
+		use crate::fourier_transform::CorrelationFourier;
+		for signal_length in 1..24 {
+			let signal: Vec<_> = (0..signal_length).map(|x| Rectangular {
+				cosine: ((x * 7) % 5) as f64 - 2.0,
+				sine: ((x * 3) % 4) as f64 - 1.5,
+			}).collect();
+			let expected = CorrelationFourier::new(signal_length).complex_analysis(&signal);
+			let transform = FastFourier::new(signal_length);
+			let bins = transform.complex_analysis(&signal);
+			assert_eq!(bins.len(), signal_length);
+			for (bin, expected) in bins.iter().zip(expected.iter()) {
+				assert!((bin.cosine - expected.cosine).abs() < 1e-9);
+				assert!((bin.sine - expected.sine).abs() < 1e-9);
+			}
+
+			let synthesis = transform.complex_synthesis(&bins);
+			for (sample, expected) in synthesis.iter().zip(signal.iter()) {
+				assert!((sample.cosine - expected.cosine).abs() < 1e-9);
+				assert!((sample.sine - expected.sine).abs() < 1e-9);
+			}
+		}
+	}
+
 	#[test]
 	fn test_convolution() {
 		let signal = [0.0, 1.0, 2.0, 3.0, 2.0, 0.0];
 		let impulse_response = [1.0, 2.0];
-		let convolution: Vec<f64> = convolution::convolve_fourier::<FastFourier>(&signal, &impulse_response)
+		let transform = FastFourier::new(signal.len() + impulse_response.len() - 1);
+		let convolution: Vec<f64> = convolution::convolve_fourier(&transform, &signal, &impulse_response)
 			.into_iter().map(math::approximate).collect();
 		assert_eq!(convolution, vec![0.0, 1.0, 2.0 + 2.0, 4.0 + 3.0, 6.0 + 2.0, 4.0, 0.0])
 	}
@@ -89,13 +291,14 @@ mod tests {
 	fn test_correlation() {
 		let signal = [0.0, 1.0, 2.0, 5.0, 2.0, 1.0];
 		let target = [1.0, 2.0];
-		let correlation = correlation::correlation::<FastFourier>(&signal, &target);
+		let correlation = correlation::correlation(&FastFourier::new(signal.len()), &signal, &target);
 		assert_eq!(math::approximate(correlation), vec![2.0, 5.0, 12.0, 9.0, 4.0, 1.0].into_iter().sum());
 	}
 
 	#[bench]
 	fn bench_analysis_and_synthesis(bench: &mut Bencher) {
 		let signal: Vec<_> = (0..8192).map(|x| x as f64).collect();
-		bench.iter(|| FastFourier::synthesis(&FastFourier::analysis(&signal), 8192));
+		let transform = FastFourier::new(8192);
+		bench.iter(|| transform.synthesis(&transform.analysis(&signal)));
 	}
-}
\ No newline at end of file
+}
@@ -1,17 +1,17 @@
-use super::Sample;
+use crate::float::Float;
 
 /// Impulse response representing the slope between samples
-pub fn first_difference() -> Vec<Sample> {
-	vec![1.0, -1.0]
+pub fn first_difference<S>() -> Vec<S> where S: Float {
+	vec![S::one(), -S::one()]
 }
 
-pub fn low_pass_square_pulse(amplitude: f64, width: usize) -> Vec<Sample> {
+pub fn low_pass_square_pulse<S>(amplitude: S, width: usize) -> Vec<S> where S: Float {
 	vec![amplitude; width]
 }
 
-pub fn high_pass_square_pulse(delta_amplitude: f64, width: usize) -> Vec<Sample> {
+pub fn high_pass_square_pulse<S>(delta_amplitude: S, width: usize) -> Vec<S> where S: Float {
 	assert!(width > 1);
-	let dampen_amplitude = delta_amplitude / (width - 1) as f64;
+	let dampen_amplitude = delta_amplitude / S::from_index(width - 1);
 	let mut filter = vec![dampen_amplitude; width];
 	filter[width / 2] = delta_amplitude;
 	filter
@@ -0,0 +1,41 @@
+use num_traits::FloatConst;
+use num_traits::NumAssign;
+use std::fmt::Debug;
+use std::iter::Sum;
+
+/// Floating point type that signals can be processed with
+/// `f64` is used by default as `f32` accumulates significantly more rounding errors
+pub trait Float: num_traits::Float + FloatConst + NumAssign + Sum + Default + Debug + Send + Sync + 'static {
+	/// Converts a constant, rounding it if the type is less precise
+	fn convert(value: f64) -> Self;
+	fn from_index(value: usize) -> Self;
+	fn into_f64(self) -> f64;
+}
+
+impl Float for f32 {
+	fn convert(value: f64) -> Self {
+		value as f32
+	}
+
+	fn from_index(value: usize) -> Self {
+		value as f32
+	}
+
+	fn into_f64(self) -> f64 {
+		self as f64
+	}
+}
+
+impl Float for f64 {
+	fn convert(value: f64) -> Self {
+		value
+	}
+
+	fn from_index(value: usize) -> Self {
+		value as f64
+	}
+
+	fn into_f64(self) -> f64 {
+		self
+	}
+}
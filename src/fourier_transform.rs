@@ -1,16 +1,30 @@
 use crate::bin::Bin;
+use crate::float::Float;
 use crate::rectangular::Rectangular;
 use std::f64;
 use std::f64::consts;
+use std::marker::PhantomData;
 use super::Sample;
 
-pub trait FourierTransform: Send + Sync {
-	fn analysis_extend(signal: &[Sample], signal_length: usize) -> Vec<Bin<Rectangular>>;
-	fn synthesis(bins: &[Bin<Rectangular>], signal_length: usize) -> Vec<Sample>;
+/// A transform planned for a single signal length
+/// Instances can be reused across frames and shared between threads
+pub trait FourierTransform<S = Sample>: Send + Sync where S: Float {
+	fn new(signal_length: usize) -> Self where Self: Sized;
 
-	fn analysis(signal: &[Sample]) -> Vec<Bin<Rectangular>> {
-		Self::analysis_extend(signal, signal.len())
-	}
+	/// Length of the signal that the transform was planned for
+	fn signal_length(&self) -> usize;
+
+	/// Signals shorter than the planned length are padded with zeros
+	fn analysis(&self, signal: &[S]) -> Vec<Bin<Rectangular<S>>>;
+	fn synthesis(&self, bins: &[Bin<Rectangular<S>>]) -> Vec<S>;
+}
+
+/// A transform of complex signals that produces every bin of the spectrum
+/// Bins in the upper half of the spectrum represent negative frequencies
+pub trait ComplexFourierTransform<S = Sample>: FourierTransform<S> where S: Float {
+	/// Signals shorter than the planned length are padded with zeros
+	fn complex_analysis(&self, signal: &[Rectangular<S>]) -> Vec<Bin<Rectangular<S>>>;
+	fn complex_synthesis(&self, bins: &[Bin<Rectangular<S>>]) -> Vec<Rectangular<S>>;
 }
 
 pub fn cosine_basis_single(bin_index: usize, signal_length: usize, index: usize) -> Sample {
@@ -29,16 +43,16 @@ pub fn sine_basis(bin_index: usize, signal_length: usize) -> Vec<Sample> {
 	(0..signal_length).map(|i| sine_basis_single(bin_index, signal_length, i)).collect()
 }
 
-pub fn normalize_cosine_amplitude(bin_index: usize, amplitude: Sample, signal_length: usize) -> Sample {
-	if bin_index == 0 || bin_index == ((signal_length + 1) / 2) {
-		amplitude / signal_length as f64
+pub fn normalize_cosine_amplitude<S>(bin_index: usize, amplitude: S, signal_length: usize) -> S where S: Float {
+	if bin_index == 0 || bin_index == signal_length.div_ceil(2) {
+		amplitude / S::from_index(signal_length)
 	} else {
-		amplitude / (signal_length as f64 / 2.0)
+		amplitude / S::convert(signal_length as f64 / 2.0)
 	}
 }
 
-pub fn normalize_sine_amplitude(amplitude: Sample, signal_length: usize) -> Sample {
-	-amplitude / (signal_length as f64 / 2.0)
+pub fn normalize_sine_amplitude<S>(amplitude: S, signal_length: usize) -> S where S: Float {
+	-amplitude / S::convert(signal_length as f64 / 2.0)
 }
 
 /// Calculates the number of bins generated for a real valued Fourier transform
@@ -46,35 +60,83 @@ pub fn bin_count(signal_length: usize) -> usize {
 	(signal_length / 2) + 1
 }
 
-pub struct CorrelationFourier();
+/// Reference transform that correlates the signal with each basis function
+pub struct CorrelationFourier<S = Sample> {
+	signal_length: usize,
+	_sample: PhantomData<S>,
+}
+
+impl<S> FourierTransform<S> for CorrelationFourier<S> where S: Float {
+	fn new(signal_length: usize) -> Self {
+		assert!(signal_length > 0);
+		CorrelationFourier { signal_length, _sample: PhantomData }
+	}
 
-impl FourierTransform for CorrelationFourier {
-	fn analysis_extend(signal: &[Sample], signal_length: usize) -> Vec<Bin<Rectangular>> {
+	fn signal_length(&self) -> usize {
+		self.signal_length
+	}
+
+	fn analysis(&self, signal: &[S]) -> Vec<Bin<Rectangular<S>>> {
+		let signal_length = self.signal_length;
+		assert!(signal.len() <= signal_length);
 		let bin_count = bin_count(signal_length);
 		(0..bin_count).map(|k| {
-			let cosine = (0..signal_length).map(|i| signal.get(i).unwrap_or(&0.0) *
-				cosine_basis_single(k, signal_length, i)).sum();
-			let sine = (0..signal_length).map(|i| -signal.get(i).unwrap_or(&0.0) *
-				sine_basis_single(k, signal_length, i)).sum();
+			let cosine = signal.iter().enumerate().map(|(i, sample)| *sample *
+				S::convert(cosine_basis_single(k, signal_length, i))).sum();
+			let sine = signal.iter().enumerate().map(|(i, sample)| -*sample *
+				S::convert(sine_basis_single(k, signal_length, i))).sum();
 			Rectangular { cosine, sine }.into()
 		}).collect()
 	}
 
-	fn synthesis(bins: &[Bin<Rectangular>], signal_length: usize) -> Vec<Sample> {
+	fn synthesis(&self, bins: &[Bin<Rectangular<S>>]) -> Vec<S> {
+		let signal_length = self.signal_length;
 		let bin_count = bin_count(signal_length);
 		assert!(bins.len() >= bin_count);
 		(0..signal_length).map(|i| {
 			(0..bin_count).map(|k| {
 				let cosine = normalize_cosine_amplitude(k, bins[k].cosine, signal_length) *
-					cosine_basis_single(k, signal_length, i);
+					S::convert(cosine_basis_single(k, signal_length, i));
 				let sine = normalize_sine_amplitude(bins[k].sine, signal_length) *
-					sine_basis_single(k, signal_length, i);
+					S::convert(sine_basis_single(k, signal_length, i));
 				cosine + sine
 			}).sum()
 		}).collect()
 	}
 }
 
+impl<S> ComplexFourierTransform<S> for CorrelationFourier<S> where S: Float {
+	fn complex_analysis(&self, signal: &[Rectangular<S>]) -> Vec<Bin<Rectangular<S>>> {
+		let signal_length = self.signal_length;
+		assert!(signal.len() <= signal_length);
+		let cosine_basis = |k, i| S::convert(cosine_basis_single(k, signal_length, i));
+		let sine_basis = |k, i| S::convert(sine_basis_single(k, signal_length, i));
+		(0..signal_length).map(|k| {
+			let cosine = signal.iter().enumerate().map(|(i, sample)|
+				sample.cosine * cosine_basis(k, i) + sample.sine * sine_basis(k, i)).sum();
+			let sine = signal.iter().enumerate().map(|(i, sample)|
+				sample.sine * cosine_basis(k, i) - sample.cosine * sine_basis(k, i)).sum();
+			Rectangular { cosine, sine }.into()
+		}).collect()
+	}
+
+	fn complex_synthesis(&self, bins: &[Bin<Rectangular<S>>]) -> Vec<Rectangular<S>> {
+		let signal_length = self.signal_length;
+		assert!(bins.len() >= signal_length);
+		let cosine_basis = |k, i| S::convert(cosine_basis_single(k, signal_length, i));
+		let sine_basis = |k, i| S::convert(sine_basis_single(k, signal_length, i));
+		let bins = &bins[..signal_length];
+		(0..signal_length).map(|i| {
+			let cosine: S = bins.iter().enumerate().map(|(k, bin)|
+				bin.cosine * cosine_basis(k, i) - bin.sine * sine_basis(k, i)).sum();
+			let sine: S = bins.iter().enumerate().map(|(k, bin)|
+				bin.sine * cosine_basis(k, i) + bin.cosine * sine_basis(k, i)).sum();
+			let signal_length = S::from_index(signal_length);
+			Rectangular { cosine: cosine / signal_length, sine: sine / signal_length }
+		}).collect()
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::math;
@@ -100,7 +162,7 @@ mod tests {
 			Bin(Rectangular { cosine: -2.5, sine: 3.4409548 }),
 			Bin(Rectangular { cosine: -2.5, sine: 0.81229924 })
 		];
-		let synthesis: Vec<_> = CorrelationFourier::synthesis(&bins, 5)
+		let synthesis: Vec<_> = CorrelationFourier::new(5).synthesis(&bins)
 			.into_iter().map(math::approximate).collect();
 		assert_eq!(synthesis, vec![1.0, 2.0, 3.0, 4.0, 5.0]);
 	}
@@ -112,16 +174,52 @@ mod tests {
 			Bin(Rectangular { cosine: -2.0, sine: 2.0 }),
 			Bin(Rectangular { cosine: -2.0, sine: 0.0 }),
 		];
-		let synthesis: Vec<_> = CorrelationFourier::synthesis(&bins, 4);
+		let synthesis: Vec<_> = CorrelationFourier::new(4).synthesis(&bins);
 		assert_eq!(synthesis, vec![1.0, 2.0, 3.0, 4.0]);
 	}
 
 	#[test]
 	fn test_analysis() {
 		let signal = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
-		let bins = CorrelationFourier::analysis(&signal);
-		let synthesis: Vec<_> = CorrelationFourier::synthesis(&bins, 6)
+		let transform = CorrelationFourier::new(6);
+		let bins = transform.analysis(&signal);
+		let synthesis: Vec<_> = transform.synthesis(&bins)
 			.into_iter().map(math::approximate).collect();
 		assert_eq!(synthesis, signal);
 	}
+
+	#[test]
+	fn test_complex_analysis() {
+		let signal = [1.0, 2.0, 3.0, 4.0, 5.0];
+		let complex_signal: Vec<_> = signal.iter().map(|x| Rectangular { cosine: *x, sine: 0.0 }).collect();
+		let transform = CorrelationFourier::new(5);
+		let bins = transform.analysis(&signal);
+		let complex_bins = transform.complex_analysis(&complex_signal);
+		assert_eq!(complex_bins.len(), 5);
+		assert_eq!(&complex_bins[..bins.len()], &bins[..]);
+		for index in 1..5 {
+			assert_eq!(math::approximate(complex_bins[index].cosine), math::approximate(complex_bins[5 - index].cosine));
+			assert_eq!(math::approximate(complex_bins[index].sine), math::approximate(-complex_bins[5 - index].sine));
+		}
+	}
+
+	#[test]
+	fn test_complex_synthesis() {
+		let signal: Vec<_> = (0..6).map(|x| Rectangular { cosine: x as f64, sine: 6.0 - (x * x) as f64 }).collect();
+		let transform = CorrelationFourier::new(6);
+		let synthesis: Vec<_> = transform.complex_synthesis(&transform.complex_analysis(&signal)).into_iter()
+			.map(|sample| Rectangular { cosine: math::approximate(sample.cosine), sine: math::approximate(sample.sine) })
+			.collect();
+		assert_eq!(synthesis, signal);
+	}
+
+	#[test]
+	fn test_analysis_padded() {
+		let transform = CorrelationFourier::new(6);
+		let bins = transform.analysis(&[1.0, 2.0, 3.0]);
+		assert_eq!(bins.len(), bin_count(6));
+		let synthesis: Vec<_> = transform.synthesis(&bins)
+			.into_iter().map(math::approximate).collect();
+		assert_eq!(synthesis, vec![1.0, 2.0, 3.0, 0.0, 0.0, 0.0]);
+	}
 }
\ No newline at end of file
@@ -0,0 +1,264 @@
+use crate::bin::Bin;
+use crate::bin_frequency;
+use crate::biquad::Coefficients;
+use crate::float::Float;
+use crate::fourier_transform::FourierTransform;
+use crate::polar::{self, Polar};
+use crate::rectangular::Rectangular;
+use std::f64::consts;
+use super::{Hertz, Sample, SampleRate};
+
+/// Magnitudes below this are treated as zeros of the transfer function
+const ZERO_MAGNITUDE: f64 = 1e-12;
+
+#[derive(Debug, Clone)]
+pub struct FrequencyResponse<S = Sample> {
+	pub frequencies: Vec<Hertz>,
+	/// Complex response with the phase unwrapped across frequencies
+	/// Frequencies should be sorted and closely spaced for the unwrapping to hold
+	pub bins: Vec<Bin<Polar<S>>>,
+	/// Group delay in samples where zeros of the transfer function have a delay of zero
+	pub group_delays: Vec<S>,
+}
+
+impl<S> FrequencyResponse<S> where S: Float {
+	pub fn magnitudes(&self) -> Vec<S> {
+		self.bins.iter().map(|bin| bin.magnitude).collect()
+	}
+
+	pub fn decibels(&self) -> Vec<S> {
+		self.bins.iter().map(|bin| S::convert(20.0) * bin.magnitude.log10()).collect()
+	}
+
+	pub fn phases(&self) -> Vec<S> {
+		self.bins.iter().map(|bin| bin.phase).collect()
+	}
+}
+
+/// Response of a finite impulse response kernel at each frequency
+pub fn kernel_response<S>(kernel: &[S], sample_rate: SampleRate, frequencies: &[Hertz]) -> FrequencyResponse<S> where S: Float {
+	let mut accumulator = Accumulator::new(frequencies.len());
+	accumulator.numerator(&evaluate(kernel, sample_rate, frequencies));
+	accumulator.finish(frequencies.to_vec())
+}
+
+/// Response of a cascade of second order sections at each frequency
+pub fn sections_response<S>(sections: &[Coefficients<S>], sample_rate: SampleRate,
+                            frequencies: &[Hertz]) -> FrequencyResponse<S> where S: Float {
+	let mut accumulator = Accumulator::new(frequencies.len());
+	for section in sections {
+		let (numerator, denominator) = polynomials(section);
+		accumulator.numerator(&evaluate(&numerator, sample_rate, frequencies));
+		accumulator.denominator(&evaluate(&denominator, sample_rate, frequencies));
+	}
+	accumulator.finish(frequencies.to_vec())
+}
+
+/// Response of a finite impulse response kernel at the bins of a transform
+/// The kernel must not be longer than the length of the transform
+pub fn kernel_grid_response<T, S>(transform: &T, kernel: &[S], sample_rate: SampleRate)
+                                  -> FrequencyResponse<S> where T: FourierTransform<S>, S: Float {
+	let mut accumulator = Accumulator::new(bin_frequency_count(transform));
+	accumulator.numerator(&analyse(transform, kernel));
+	accumulator.finish(grid_frequencies(transform, sample_rate))
+}
+
+/// Response of a cascade of second order sections at the bins of a transform
+pub fn sections_grid_response<T, S>(transform: &T, sections: &[Coefficients<S>], sample_rate: SampleRate)
+                                    -> FrequencyResponse<S> where T: FourierTransform<S>, S: Float {
+	let mut accumulator = Accumulator::new(bin_frequency_count(transform));
+	for section in sections {
+		let (numerator, denominator) = polynomials(section);
+		accumulator.numerator(&analyse(transform, &numerator));
+		accumulator.denominator(&analyse(transform, &denominator));
+	}
+	accumulator.finish(grid_frequencies(transform, sample_rate))
+}
+
+/// Frequencies of the bins produced by a transform from zero to the Nyquist frequency
+pub fn grid_frequencies<T, S>(transform: &T, sample_rate: SampleRate) -> Vec<Hertz> where T: FourierTransform<S>, S: Float {
+	let bin_width = bin_frequency::complex_bin_width(sample_rate, transform.signal_length());
+	(0..bin_frequency_count(transform)).map(|index| bin_width * index as f64).collect()
+}
+
+fn bin_frequency_count<T, S>(transform: &T) -> usize where T: FourierTransform<S>, S: Float {
+	crate::fourier_transform::bin_count(transform.signal_length())
+}
+
+fn polynomials<S>(section: &Coefficients<S>) -> ([S; 3], [S; 3]) where S: Float {
+	([section.b0, section.b1, section.b2], [S::one(), section.a1, section.a2])
+}
+
+/// Polynomial in `z^-1` and its ramp weighted counterpart evaluated at one frequency
+struct Evaluation {
+	value: Bin<Rectangular<f64>>,
+	ramp: Bin<Rectangular<f64>>,
+}
+
+fn evaluate<S>(polynomial: &[S], sample_rate: SampleRate, frequencies: &[Hertz]) -> Vec<Evaluation> where S: Float {
+	frequencies.iter().map(|frequency| {
+		let angular = -2.0 * consts::PI * frequency / sample_rate as f64;
+		let zero = Bin(Rectangular { cosine: 0.0, sine: 0.0 });
+		polynomial.iter().enumerate().fold(Evaluation { value: zero, ramp: zero }, |evaluation, (index, coefficient)| {
+			let (sine, cosine) = (angular * index as f64).sin_cos();
+			let term = Bin(Rectangular { cosine, sine }) * coefficient.into_f64();
+			Evaluation { value: evaluation.value + term, ramp: evaluation.ramp + term * index as f64 }
+		})
+	}).collect()
+}
+
+fn analyse<T, S>(transform: &T, polynomial: &[S]) -> Vec<Evaluation> where T: FourierTransform<S>, S: Float {
+	assert!(polynomial.len() <= transform.signal_length());
+	let ramp: Vec<_> = polynomial.iter().enumerate().map(|(index, coefficient)| *coefficient * S::from_index(index)).collect();
+	let convert = |bin: Bin<Rectangular<S>>| Bin(Rectangular { cosine: bin.cosine.into_f64(), sine: bin.sine.into_f64() });
+	transform.analysis(polynomial).into_iter().zip(transform.analysis(&ramp))
+		.map(|(value, ramp)| Evaluation { value: convert(value), ramp: convert(ramp) }).collect()
+}
+
+/// Multiplies numerators and divides denominators while summing their group delays
+struct Accumulator {
+	response: Vec<Bin<Rectangular<f64>>>,
+	group_delays: Vec<f64>,
+}
+
+impl Accumulator {
+	fn new(length: usize) -> Self {
+		Accumulator {
+			response: vec![Bin(Rectangular { cosine: 1.0, sine: 0.0 }); length],
+			group_delays: vec![0.0; length],
+		}
+	}
+
+	fn numerator(&mut self, evaluations: &[Evaluation]) {
+		self.accumulate(evaluations, false);
+	}
+
+	fn denominator(&mut self, evaluations: &[Evaluation]) {
+		self.accumulate(evaluations, true);
+	}
+
+	fn accumulate(&mut self, evaluations: &[Evaluation], inverse: bool) {
+		let iterator = self.response.iter_mut().zip(self.group_delays.iter_mut());
+		for ((response, group_delay), evaluation) in iterator.zip(evaluations) {
+			let delay = if evaluation.value.abs() > ZERO_MAGNITUDE { (evaluation.ramp / evaluation.value).cosine } else { 0.0 };
+			if inverse {
+				*response /= evaluation.value;
+				*group_delay -= delay;
+			} else {
+				*response *= evaluation.value;
+				*group_delay += delay;
+			}
+		}
+	}
+
+	fn finish<S>(self, frequencies: Vec<Hertz>) -> FrequencyResponse<S> where S: Float {
+		let response: Vec<_> = self.response.into_iter().map(|bin|
+			Bin(Rectangular { cosine: S::convert(bin.cosine), sine: S::convert(bin.sine) })).collect();
+		let group_delays = self.group_delays.into_iter().map(S::convert).collect();
+		FrequencyResponse { frequencies, bins: polar::to_polar_spectrum(&response), group_delays }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::biquad::Cascade;
+	use crate::filter_kernels;
+	use crate::fourier_transform::CorrelationFourier;
+	use crate::window::Hann;
+	use crate::windowed_sinc;
+	use super::*;
+
+	const SAMPLE_RATE: SampleRate = 8000;
+
+	fn frequencies(end: Hertz, count: usize) -> Vec<Hertz> {
+		(0..count).map(|index| end * index as f64 / count as f64).collect()
+	}
+
+	#[test]
+	fn test_moving_average() {
+		let width = 9;
+		let kernel = filter_kernels::low_pass_square_pulse(1.0 / width as f64, width);
+		let frequencies = frequencies(SAMPLE_RATE as f64 / width as f64, 50);
+		let response = kernel_response(&kernel, SAMPLE_RATE, &frequencies);
+		for (index, frequency) in frequencies.iter().enumerate() {
+			let angular = consts::PI * frequency / SAMPLE_RATE as f64;
+			let expected = if index == 0 { 1.0 } else { (angular * width as f64).sin() / (width as f64 * angular.sin()) };
+			assert!((response.bins[index].magnitude - expected).abs() < 1e-12);
+			assert!((response.group_delays[index] - 4.0).abs() < 1e-9);
+			assert!((response.bins[index].phase + 2.0 * angular * 4.0).abs() < 1e-9);
+		}
+	}
+
+	#[test]
+	fn test_linear_phase() {
+		let kernel = windowed_sinc::low_pass::<f64, _>(&Hann(), 0.1, 31);
+		let frequencies = frequencies(800.0, 100);
+		let response = kernel_response(&kernel, SAMPLE_RATE, &frequencies);
+		let last_phase = *response.phases().last().unwrap();
+		assert!(last_phase < -consts::PI);
+		for (frequency, phase) in frequencies.iter().zip(response.phases()) {
+			assert!((phase + 2.0 * consts::PI * frequency / SAMPLE_RATE as f64 * 15.0).abs() < 1e-9);
+		}
+		response.group_delays.iter().for_each(|delay| assert!((delay - 15.0).abs() < 1e-9));
+		assert!(response.decibels()[0].abs() < 1e-9);
+	}
+
+	#[test]
+	fn test_kernel_grid() {
+		let kernel = windowed_sinc::low_pass::<f64, _>(&Hann(), 0.2, 21);
+		let transform = CorrelationFourier::new(64);
+		let grid = kernel_grid_response(&transform, &kernel, SAMPLE_RATE);
+		assert_eq!(grid.frequencies.len(), 33);
+		assert_eq!(grid.frequencies[32], 4000.0);
+		let direct = kernel_response(&kernel, SAMPLE_RATE, &grid.frequencies);
+		for (grid, direct) in grid.bins.iter().zip(direct.bins.iter()) {
+			assert!((grid.magnitude - direct.magnitude).abs() < 1e-9);
+			assert!((grid.phase - direct.phase).abs() < 1e-9);
+		}
+		for (grid, direct) in grid.group_delays.iter().zip(direct.group_delays.iter()) {
+			assert!((grid - direct).abs() < 1e-9);
+		}
+
+		// Bins of odd length transforms stop short of the Nyquist frequency
+		let transform = CorrelationFourier::new(25);
+		let grid = kernel_grid_response(&transform, &kernel, SAMPLE_RATE);
+		assert_eq!(grid.frequencies.len(), 13);
+		assert_eq!(grid.frequencies[5], 5.0 * SAMPLE_RATE as f64 / 25.0);
+		let direct = kernel_response(&kernel, SAMPLE_RATE, &grid.frequencies);
+		for (grid, direct) in grid.bins.iter().zip(direct.bins.iter()) {
+			assert!((grid.magnitude - direct.magnitude).abs() < 1e-9);
+		}
+	}
+
+	#[test]
+	fn test_sections() {
+		let sections = [
+			Coefficients::low_pass(SAMPLE_RATE, 1000.0, consts::FRAC_1_SQRT_2),
+			Coefficients::peaking(SAMPLE_RATE, 2000.0, 2.0, 6.0),
+		];
+		let mut cascade: Cascade = Cascade::new(&sections, crate::biquad::Form::DirectOne);
+		let mut impulse = vec![0.0; 4096];
+		impulse[0] = 1.0;
+		let frequencies = frequencies(4000.0, 400);
+		let response = sections_response(&sections, SAMPLE_RATE, &frequencies);
+		let impulse_response = kernel_response(&cascade.process(&impulse), SAMPLE_RATE, &frequencies);
+		for (bin, expected) in response.bins.iter().zip(impulse_response.bins.iter()) {
+			assert!((bin.magnitude - expected.magnitude).abs() < 1e-9);
+		}
+
+		// Group delay is the negative derivative of the unwrapped phase
+		let step = 2.0 * consts::PI * (frequencies[1] - frequencies[0]) / SAMPLE_RATE as f64;
+		for index in 1..frequencies.len() - 1 {
+			let derivative = (response.bins[index + 1].phase - response.bins[index - 1].phase) / (2.0 * step);
+			assert!((response.group_delays[index] + derivative).abs() < 1e-2, "{}", index);
+		}
+
+		let transform = CorrelationFourier::new(800);
+		let grid = sections_grid_response(&transform, &sections, SAMPLE_RATE);
+		for (index, bin) in grid.bins.iter().take(400).enumerate() {
+			assert!((bin.magnitude - response.bins[index].magnitude).abs() < 1e-9);
+			assert!((bin.phase - response.bins[index].phase).abs() < 1e-9);
+		}
+		assert!((sections_response(&sections[..1], SAMPLE_RATE, &[1000.0]).decibels()[0] + 3.0103).abs() < 1e-4);
+	}
+}
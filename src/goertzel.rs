@@ -0,0 +1,161 @@
+use crate::bin::Bin;
+use crate::bin_frequency;
+use crate::float::Float;
+use crate::rectangular::Rectangular;
+use std::f64::consts;
+use super::{Hertz, Sample, SampleRate};
+
+/// Streaming resonator that accumulates samples for a single frequency
+/// The generalised form allows frequencies between the central frequencies of bins
+#[derive(Debug, Clone)]
+pub struct Goertzel<S = Sample> {
+	angular_frequency: f64,
+	coefficient: S,
+	previous: S,
+	second_previous: S,
+	sample_count: usize,
+}
+
+impl<S> Goertzel<S> where S: Float {
+	pub fn new(frequency: Hertz, sample_rate: SampleRate) -> Self {
+		assert!(sample_rate > 0);
+		Self::angular(2.0 * consts::PI * frequency / sample_rate as f64)
+	}
+
+	/// Targets the bin of a complex valued Fourier transform of `signal_length`
+	/// with the closest central frequency, as in the classic Goertzel algorithm
+	pub fn nearest_bin(frequency: Hertz, sample_rate: SampleRate, signal_length: usize) -> Self {
+		let bin_index = bin_frequency::nearest_bin_index(sample_rate, signal_length, frequency);
+		let frequency = bin_frequency::signed_bin_frequency(sample_rate, signal_length, bin_index);
+		Self::new(frequency, sample_rate)
+	}
+
+	/// Targets a possibly fractional bin index of a transform of `signal_length`
+	pub fn bin_index(bin_index: f64, signal_length: usize) -> Self {
+		assert!(signal_length > 0);
+		Self::angular(2.0 * consts::PI * bin_index / signal_length as f64)
+	}
+
+	fn angular(angular_frequency: f64) -> Self {
+		Goertzel {
+			angular_frequency,
+			coefficient: S::convert(2.0 * angular_frequency.cos()),
+			previous: S::zero(),
+			second_previous: S::zero(),
+			sample_count: 0,
+		}
+	}
+
+	pub fn push(&mut self, sample: S) {
+		let current = sample + self.coefficient * self.previous - self.second_previous;
+		self.second_previous = self.previous;
+		self.previous = current;
+		self.sample_count += 1;
+	}
+
+	pub fn push_samples(&mut self, samples: &[S]) {
+		samples.iter().for_each(|sample| self.push(*sample));
+	}
+
+	/// Evaluates the spectrum of every sample pushed since the last reset
+	pub fn bin(&self) -> Bin<Rectangular<S>> {
+		if self.sample_count == 0 {
+			return Bin(Rectangular { cosine: S::zero(), sine: S::zero() });
+		}
+
+		let (sine, cosine) = self.angular_frequency.sin_cos();
+		let output = Rectangular {
+			cosine: self.previous - S::convert(cosine) * self.second_previous,
+			sine: S::convert(sine) * self.second_previous,
+		};
+
+		// Shifts the phase so that it is relative to the first sample
+		let shift = -self.angular_frequency * (self.sample_count - 1) as f64;
+		let shift = Rectangular { cosine: S::convert(shift.cos()), sine: S::convert(shift.sin()) };
+		Bin(output) * Bin(shift)
+	}
+
+	/// Squared magnitude of the bin which does not require the phase correction
+	pub fn power(&self) -> S {
+		self.previous * self.previous + self.second_previous * self.second_previous -
+			self.coefficient * self.previous * self.second_previous
+	}
+
+	pub fn sample_count(&self) -> usize {
+		self.sample_count
+	}
+
+	pub fn reset(&mut self) {
+		self.previous = S::zero();
+		self.second_previous = S::zero();
+		self.sample_count = 0;
+	}
+}
+
+/// Evaluates a single frequency of the spectrum of a block of samples
+pub fn goertzel<S>(signal: &[S], frequency: Hertz, sample_rate: SampleRate) -> Bin<Rectangular<S>> where S: Float {
+	let mut goertzel = Goertzel::new(frequency, sample_rate);
+	goertzel.push_samples(signal);
+	goertzel.bin()
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::fourier_transform::{CorrelationFourier, FourierTransform};
+	use crate::math::{self, test_signal};
+	use super::*;
+
+	#[test]
+	fn test_bin_index() {
+		let signal = test_signal(16);
+		let bins = CorrelationFourier::new(16).analysis(&signal);
+		for (bin_index, expected) in bins.into_iter().enumerate() {
+			let mut goertzel = Goertzel::bin_index(bin_index as f64, 16);
+			goertzel.push_samples(&signal);
+			math::assert_bins(&[goertzel.bin()], &[expected], 1e-9);
+			assert!((goertzel.power() - goertzel.bin().norm_sqr()).abs() < 1e-9);
+		}
+	}
+
+	#[test]
+	fn test_fractional_frequency() {
+		let signal = test_signal(37);
+		let frequency = 1234.5;
+		let angular_frequency = 2.0 * consts::PI * frequency / 8000.0;
+		let expected = Rectangular {
+			cosine: signal.iter().enumerate().map(|(n, x)| x * (angular_frequency * n as f64).cos()).sum(),
+			sine: signal.iter().enumerate().map(|(n, x)| -x * (angular_frequency * n as f64).sin()).sum(),
+		};
+		math::assert_bins(&[goertzel(&signal, frequency, 8000)], &[Bin(expected)], 1e-9);
+	}
+
+	#[test]
+	fn test_nearest_bin() {
+		let signal = test_signal(64);
+		let bins = CorrelationFourier::new(64).analysis(&signal);
+		let mut goertzel = Goertzel::nearest_bin(1000.0, 40000, 64);
+		goertzel.push_samples(&signal);
+		math::assert_bins(&[goertzel.bin()], &[bins[2]], 1e-9);
+	}
+
+	#[test]
+	fn test_streaming() {
+		let signal = test_signal(100);
+		let mut goertzel = Goertzel::new(440.0, 44100);
+		signal.chunks(7).for_each(|chunk| goertzel.push_samples(chunk));
+		assert_eq!(goertzel.sample_count(), 100);
+		math::assert_bins(&[goertzel.bin()], &[self::goertzel(&signal, 440.0, 44100)], 1e-9);
+
+		goertzel.reset();
+		goertzel.push_samples(&signal[..10]);
+		math::assert_bins(&[goertzel.bin()], &[self::goertzel(&signal[..10], 440.0, 44100)], 1e-9);
+	}
+
+	#[test]
+	fn test_tone_detection() {
+		let signal: Vec<_> = (0..8000).map(|x| (2.0 * consts::PI * 1000.0 * x as f64 / 8000.0).sin()).collect();
+		let tone = goertzel(&signal, 1000.0, 8000).abs();
+		assert!((tone - 4000.0).abs() < 1e-6);
+		assert!(goertzel(&signal, 1100.0, 8000).abs() < 1e-6);
+	}
+}
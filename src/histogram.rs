@@ -42,6 +42,12 @@ impl Histogram {
 	}
 }
 
+impl Default for Histogram {
+	fn default() -> Self {
+		Histogram::new()
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::statistics;
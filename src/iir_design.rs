@@ -0,0 +1,574 @@
+use crate::bin::Bin;
+use crate::biquad::{Cascade, Coefficients, Form};
+use crate::float::Float;
+use crate::polar::Polar;
+use crate::rectangular::Rectangular;
+use std::f64::consts;
+use super::{Hertz, SampleRate};
+
+type Complex = Bin<Rectangular<f64>>;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Prototype {
+	/// Maximally flat passband with a gain of `1 / sqrt(2)` at the cutoff
+	Butterworth,
+	/// Passband ripple in decibels with the cutoff at the edge of the ripple
+	ChebyshevOne { ripple: f64 },
+	/// Stopband attenuation in decibels with the cutoff at the start of the stopband
+	ChebyshevTwo { attenuation: f64 },
+	/// Ripple in both bands with the cutoff at the edge of the passband ripple
+	Elliptic { ripple: f64, attenuation: f64 },
+	/// Maximally flat group delay with a gain of `1 / sqrt(2)` at the cutoff
+	Bessel,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BandType {
+	LowPass(Hertz),
+	HighPass(Hertz),
+	BandPass(Hertz, Hertz),
+	BandStop(Hertz, Hertz),
+}
+
+/// Factored transfer function `gain * Π(s - zero) / Π(s - pole)`
+#[derive(Debug, Clone)]
+pub struct ZerosPolesGain {
+	pub zeros: Vec<Complex>,
+	pub poles: Vec<Complex>,
+	pub gain: f64,
+}
+
+impl ZerosPolesGain {
+	/// Evaluates the digital transfer function on the unit circle
+	pub fn response(&self, sample_rate: SampleRate, frequency: Hertz) -> Complex {
+		let point = complex_polar(1.0, 2.0 * consts::PI * frequency / sample_rate as f64);
+		let numerator = product(self.zeros.iter().map(|zero| point - *zero));
+		let denominator = product(self.poles.iter().map(|pole| point - *pole));
+		numerator / denominator * self.gain
+	}
+
+	/// Evaluates the analog transfer function at an angular frequency
+	pub fn analog_response(&self, angular_frequency: f64) -> Complex {
+		let point = complex(0.0, angular_frequency);
+		let numerator = product(self.zeros.iter().map(|zero| point - *zero));
+		let denominator = product(self.poles.iter().map(|pole| point - *pole));
+		numerator / denominator * self.gain
+	}
+
+	/// Magnitude of the digital frequency response
+	pub fn gain(&self, sample_rate: SampleRate, frequency: Hertz) -> f64 {
+		self.response(sample_rate, frequency).abs()
+	}
+
+	/// Groups conjugate pairs of digital poles and zeros into second order sections
+	/// Sections with poles closest to the unit circle are placed last
+	pub fn second_order_sections<S>(&self) -> Vec<Coefficients<S>> where S: Float {
+		let mut zero_groups = conjugate_groups(&self.zeros);
+		let mut pole_groups = conjugate_groups(&self.poles);
+		let distance = |group: &Vec<Complex>| (1.0 - group[0].abs()).abs();
+		pole_groups.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+		zero_groups.resize(usize::max(zero_groups.len(), pole_groups.len()), Vec::new());
+		pole_groups.resize(zero_groups.len(), Vec::new());
+
+		let mut sections = Vec::new();
+		for poles in pole_groups {
+			let nearest = (0..zero_groups.len()).min_by(|a, b| {
+				let separation = |group: &Vec<Complex>| match (group.first(), poles.first()) {
+					(Some(zero), Some(pole)) => (*zero - *pole).abs(),
+					_ => f64::INFINITY,
+				};
+				separation(&zero_groups[*a]).total_cmp(&separation(&zero_groups[*b]))
+			}).unwrap();
+			let zeros = zero_groups.remove(nearest);
+			let [b0, b1, b2] = polynomial(&zeros);
+			let [a0, a1, a2] = polynomial(&poles);
+			sections.push([b0, b1, b2, a0, a1, a2]);
+		}
+
+		sections.reverse();
+		if let Some(first) = sections.first_mut() {
+			(0..3).for_each(|index| first[index] *= self.gain);
+		}
+		sections.into_iter().map(|[b0, b1, b2, a0, a1, a2]| Coefficients::new(b0, b1, b2, a0, a1, a2)).collect()
+	}
+
+	pub fn cascade<S>(&self, form: Form) -> Cascade<S> where S: Float {
+		Cascade::new(&self.second_order_sections(), form)
+	}
+}
+
+/// Designs a digital filter of an order where band filters have twice as many poles
+/// The prototype is transformed into the band and mapped with the prewarped bilinear transform
+pub fn design(prototype: Prototype, order: usize, band: BandType, sample_rate: SampleRate) -> ZerosPolesGain {
+	let nyquist = sample_rate as f64 / 2.0;
+	let prewarp = |frequency: Hertz| {
+		assert!(frequency > 0.0 && frequency < nyquist);
+		2.0 * sample_rate as f64 * (consts::PI * frequency / sample_rate as f64).tan()
+	};
+
+	let prototype = analog_prototype(prototype, order);
+	let analog = match band {
+		BandType::LowPass(frequency) => low_pass(prototype, prewarp(frequency)),
+		BandType::HighPass(frequency) => high_pass(prototype, prewarp(frequency)),
+		BandType::BandPass(low, high) => {
+			assert!(low < high);
+			let (low, high) = (prewarp(low), prewarp(high));
+			band_pass(prototype, (low * high).sqrt(), high - low)
+		}
+		BandType::BandStop(low, high) => {
+			assert!(low < high);
+			let (low, high) = (prewarp(low), prewarp(high));
+			band_stop(prototype, (low * high).sqrt(), high - low)
+		}
+	};
+	bilinear(analog, sample_rate)
+}
+
+/// Analog low pass prototype with a cutoff of one radian per second
+pub fn analog_prototype(prototype: Prototype, order: usize) -> ZerosPolesGain {
+	assert!(order > 0);
+	let order_float = order as f64;
+	let angles = || (0..order).map(|index| {
+		let index = 2.0 * index as f64 + 1.0 - order_float;
+		consts::PI * index / (2.0 * order_float)
+	});
+
+	match prototype {
+		Prototype::Butterworth => {
+			let poles = angles().map(|angle| -complex_polar(1.0, angle)).collect();
+			ZerosPolesGain { zeros: Vec::new(), poles, gain: 1.0 }
+		}
+		Prototype::ChebyshevOne { ripple } => {
+			assert!(ripple > 0.0);
+			let epsilon = (10.0_f64.powf(ripple / 10.0) - 1.0).sqrt();
+			let mu = (1.0 / epsilon).asinh() / order_float;
+			let poles: Vec<_> = angles().map(|angle| complex(-mu.sinh() * angle.cos(), -mu.cosh() * angle.sin())).collect();
+			let mut gain = product(poles.iter().map(|pole| -*pole)).cosine;
+			if order.is_multiple_of(2) {
+				gain /= (1.0 + epsilon * epsilon).sqrt();
+			}
+			ZerosPolesGain { zeros: Vec::new(), poles, gain }
+		}
+		Prototype::ChebyshevTwo { attenuation } => {
+			assert!(attenuation > 0.0);
+			let epsilon = 1.0 / (10.0_f64.powf(attenuation / 10.0) - 1.0).sqrt();
+			let mu = (1.0 / epsilon).asinh() / order_float;
+			let zeros: Vec<_> = angles().filter(|angle| angle.abs() > 1e-12)
+				.map(|angle| complex(0.0, 1.0 / angle.sin())).collect();
+			let poles: Vec<_> = angles().map(|angle| {
+				let pole = -complex_polar(1.0, angle);
+				complex(1.0, 0.0) / complex(mu.sinh() * pole.cosine, mu.cosh() * pole.sine)
+			}).collect();
+			let gain = (product(poles.iter().map(|pole| -*pole)) / product(zeros.iter().map(|zero| -*zero))).cosine;
+			ZerosPolesGain { zeros, poles, gain }
+		}
+		Prototype::Elliptic { ripple, attenuation } => elliptic(order, ripple, attenuation),
+		Prototype::Bessel => bessel(order),
+	}
+}
+
+fn low_pass(prototype: ZerosPolesGain, angular_frequency: f64) -> ZerosPolesGain {
+	let degree = prototype.poles.len() - prototype.zeros.len();
+	ZerosPolesGain {
+		zeros: prototype.zeros.iter().map(|zero| *zero * angular_frequency).collect(),
+		poles: prototype.poles.iter().map(|pole| *pole * angular_frequency).collect(),
+		gain: prototype.gain * angular_frequency.powi(degree as i32),
+	}
+}
+
+fn high_pass(prototype: ZerosPolesGain, angular_frequency: f64) -> ZerosPolesGain {
+	let degree = prototype.poles.len() - prototype.zeros.len();
+	let invert = |value: &Complex| complex(angular_frequency, 0.0) / *value;
+	let mut zeros: Vec<_> = prototype.zeros.iter().map(invert).collect();
+	zeros.extend(std::iter::repeat_n(complex(0.0, 0.0), degree));
+	let gain = prototype.gain * (product(prototype.zeros.iter().map(|zero| -*zero)) /
+		product(prototype.poles.iter().map(|pole| -*pole))).cosine;
+	ZerosPolesGain { zeros, poles: prototype.poles.iter().map(invert).collect(), gain }
+}
+
+/// Maps each root of the prototype to the two roots `x ± sqrt(x² - center²)`
+fn split_roots(roots: impl Iterator<Item=Complex>, center: f64) -> Vec<Complex> {
+	roots.flat_map(|root| {
+		let offset = sqrt(root * root - complex(center * center, 0.0));
+		vec![root + offset, root - offset]
+	}).collect()
+}
+
+fn band_pass(prototype: ZerosPolesGain, center: f64, bandwidth: f64) -> ZerosPolesGain {
+	let degree = prototype.poles.len() - prototype.zeros.len();
+	let mut zeros = split_roots(prototype.zeros.iter().map(|zero| *zero * (bandwidth / 2.0)), center);
+	zeros.extend(std::iter::repeat_n(complex(0.0, 0.0), degree));
+	let poles = split_roots(prototype.poles.iter().map(|pole| *pole * (bandwidth / 2.0)), center);
+	ZerosPolesGain { zeros, poles, gain: prototype.gain * bandwidth.powi(degree as i32) }
+}
+
+fn band_stop(prototype: ZerosPolesGain, center: f64, bandwidth: f64) -> ZerosPolesGain {
+	let degree = prototype.poles.len() - prototype.zeros.len();
+	let invert = |value: &Complex| complex(bandwidth / 2.0, 0.0) / *value;
+	let mut zeros = split_roots(prototype.zeros.iter().map(invert), center);
+	zeros.extend(std::iter::repeat_n(complex(0.0, center), degree));
+	zeros.extend(std::iter::repeat_n(complex(0.0, -center), degree));
+	let poles = split_roots(prototype.poles.iter().map(invert), center);
+	let gain = prototype.gain * (product(prototype.zeros.iter().map(|zero| -*zero)) /
+		product(prototype.poles.iter().map(|pole| -*pole))).cosine;
+	ZerosPolesGain { zeros, poles, gain }
+}
+
+fn bilinear(analog: ZerosPolesGain, sample_rate: SampleRate) -> ZerosPolesGain {
+	let degree = analog.poles.len() - analog.zeros.len();
+	let double_rate = complex(2.0 * sample_rate as f64, 0.0);
+	let map = |root: &Complex| (double_rate + *root) / (double_rate - *root);
+	let mut zeros: Vec<_> = analog.zeros.iter().map(map).collect();
+	zeros.extend(std::iter::repeat_n(complex(-1.0, 0.0), degree));
+	let gain = analog.gain * (product(analog.zeros.iter().map(|zero| double_rate - *zero)) /
+		product(analog.poles.iter().map(|pole| double_rate - *pole))).cosine;
+	ZerosPolesGain { zeros, poles: analog.poles.iter().map(map).collect(), gain }
+}
+
+/// Poles of the reverse Bessel polynomial scaled so that the cutoff is one radian per second
+fn bessel(order: usize) -> ZerosPolesGain {
+	let coefficients: Vec<f64> = (0..=order).map(|index| {
+		let numerator: f64 = ((order - index + 1)..=(2 * order - index)).map(|x| x as f64).product();
+		let denominator: f64 = (1..=index).map(|x| x as f64).product();
+		numerator / (denominator * 2.0_f64.powi((order - index) as i32))
+	}).collect();
+	let poles = polynomial_roots(&coefficients);
+
+	// Finds the frequency where the magnitude falls to half power
+	let delay_normalised = ZerosPolesGain { zeros: Vec::new(), poles: poles.clone(), gain: coefficients[0] };
+	let (mut lower, mut upper) = (0.0, 2.0 * order as f64 + 2.0);
+	for _ in 0..200 {
+		let middle = (lower + upper) / 2.0;
+		if delay_normalised.analog_response(middle).abs() > consts::FRAC_1_SQRT_2 {
+			lower = middle;
+		} else {
+			upper = middle;
+		}
+	}
+
+	let poles: Vec<_> = poles.into_iter().map(|pole| pole / lower).collect();
+	let gain = product(poles.iter().map(|pole| -*pole)).cosine;
+	ZerosPolesGain { zeros: Vec::new(), poles, gain }
+}
+
+/// Roots of a monic polynomial with coefficients in ascending powers (Durand-Kerner)
+fn polynomial_roots(coefficients: &[f64]) -> Vec<Complex> {
+	let degree = coefficients.len() - 1;
+	let evaluate = |point: Complex| coefficients.iter().rev()
+		.fold(complex(0.0, 0.0), |sum, coefficient| sum * point + complex(*coefficient, 0.0));
+	let seed = complex(0.4, 0.9);
+	let mut roots: Vec<_> = (0..degree).map(|index| Bin(seed.powf(index as f64))).collect();
+	for _ in 0..1000 {
+		let mut change: f64 = 0.0;
+		for index in 0..degree {
+			let root = roots[index];
+			let denominator = product(roots.iter().enumerate()
+				.filter(|(other, _)| *other != index).map(|(_, other)| root - *other));
+			let step = evaluate(root) / denominator;
+			roots[index] = root - step;
+			change = change.max(step.abs() / root.abs().max(1.0));
+		}
+
+		if change < 1e-15 {
+			break;
+		}
+	}
+	roots
+}
+
+/// Elliptic prototype using Landen transformations (Orfanidis)
+fn elliptic(order: usize, ripple: f64, attenuation: f64) -> ZerosPolesGain {
+	assert!(ripple > 0.0 && attenuation > ripple);
+	let passband_epsilon = (10.0_f64.powf(ripple / 10.0) - 1.0).sqrt();
+	let stopband_epsilon = (10.0_f64.powf(attenuation / 10.0) - 1.0).sqrt();
+	let discrimination = passband_epsilon / stopband_epsilon;
+	let selectivity = elliptic_degree(order, discrimination);
+
+	let v0 = complex(0.0, -1.0) * asne(complex(0.0, 1.0 / passband_epsilon), discrimination) / order as f64;
+	let (mut zeros, mut poles) = (Vec::new(), Vec::new());
+	for index in 1..=order / 2 {
+		let u = complex((2 * index - 1) as f64 / order as f64, 0.0);
+		let zero = complex(0.0, 1.0) / (cde(u, selectivity) * selectivity);
+		let pole = complex(0.0, 1.0) * cde(u - complex(0.0, 1.0) * v0, selectivity);
+		zeros.extend(vec![zero, Bin(zero.conj())]);
+		poles.extend(vec![pole, Bin(pole.conj())]);
+	}
+
+	if !order.is_multiple_of(2) {
+		let pole = complex(0.0, 1.0) * sne(complex(0.0, 1.0) * v0, selectivity);
+		poles.push(complex(pole.cosine, 0.0));
+	}
+
+	let direct_gain = if order.is_multiple_of(2) { 1.0 / (1.0 + passband_epsilon * passband_epsilon).sqrt() } else { 1.0 };
+	let gain = direct_gain * (product(poles.iter().map(|pole| -*pole)) /
+		product(zeros.iter().map(|zero| -*zero))).cosine;
+	ZerosPolesGain { zeros, poles, gain }
+}
+
+/// Solves the degree equation for the selectivity modulus
+fn elliptic_degree(order: usize, discrimination: f64) -> f64 {
+	let complement = (1.0 - discrimination * discrimination).sqrt();
+	let complement_selectivity = (1..=order / 2).fold(complement.powi(order as i32), |product, index| {
+		let u = complex((2 * index - 1) as f64 / order as f64, 0.0);
+		product * sne(u, complement).cosine.powi(4)
+	});
+	(1.0 - complement_selectivity * complement_selectivity).sqrt()
+}
+
+/// Descending sequence of moduli from Landen transformations
+fn landen(modulus: f64) -> Vec<f64> {
+	let mut moduli = Vec::new();
+	let mut modulus = modulus;
+	while modulus > f64::EPSILON && moduli.len() < 64 {
+		modulus = (modulus / (1.0 + (1.0 - modulus * modulus).sqrt())).powi(2);
+		moduli.push(modulus);
+	}
+	moduli
+}
+
+/// Complete elliptic integrals of the modulus and its complement
+fn elliptic_integrals(modulus: f64) -> (f64, f64) {
+	let integral = |modulus: f64| landen(modulus).iter().fold(consts::FRAC_PI_2, |product, modulus| product * (1.0 + modulus));
+	(integral(modulus), integral((1.0 - modulus * modulus).sqrt()))
+}
+
+/// Ascending Landen transformation of `w` through the moduli
+fn ascend(moduli: &[f64], w: Complex) -> Complex {
+	moduli.iter().rev().fold(w, |w, modulus| w * (1.0 + modulus) / (complex(1.0, 0.0) + w * w * *modulus))
+}
+
+/// Jacobi elliptic function `cd(uK, k)`
+fn cde(u: Complex, modulus: f64) -> Complex {
+	ascend(&landen(modulus), cos(u * consts::FRAC_PI_2))
+}
+
+/// Jacobi elliptic function `sn(uK, k)`
+fn sne(u: Complex, modulus: f64) -> Complex {
+	ascend(&landen(modulus), sin(u * consts::FRAC_PI_2))
+}
+
+/// Inverse of `cde` with the real part reduced to a period of four
+fn acde(w: Complex, modulus: f64) -> Complex {
+	let moduli = landen(modulus);
+	let mut w = w;
+	for (index, next) in moduli.iter().enumerate() {
+		let previous = if index == 0 { modulus } else { moduli[index - 1] };
+		let root = sqrt(complex(1.0, 0.0) - w * w * (previous * previous));
+		w = w / (complex(1.0, 0.0) + root) * (2.0 / (1.0 + next));
+	}
+
+	let u = acos(w) * (2.0 / consts::PI);
+	let (integral, complement) = elliptic_integrals(modulus);
+	let symmetric_remainder = |x: f64, y: f64| {
+		let remainder = x % y;
+		if remainder.abs() > y / 2.0 { remainder - y * remainder.signum() } else { remainder }
+	};
+	complex(symmetric_remainder(u.cosine, 4.0), symmetric_remainder(u.sine, 2.0 * complement / integral))
+}
+
+fn asne(w: Complex, modulus: f64) -> Complex {
+	complex(1.0, 0.0) - acde(w, modulus)
+}
+
+/// Splits roots into conjugate pairs and pairs of real roots
+fn conjugate_groups(roots: &[Complex]) -> Vec<Vec<Complex>> {
+	let tolerance = 1e-9;
+	let is_real = |root: &Complex| root.sine.abs() <= tolerance * root.abs().max(1.0);
+	let mut real: Vec<_> = roots.iter().filter(|root| is_real(root))
+		.map(|root| complex(root.cosine, 0.0)).collect();
+	real.sort_by(|a, b| a.cosine.total_cmp(&b.cosine));
+
+	let mut conjugates: Vec<_> = roots.iter().filter(|root| !is_real(root) && root.sine < 0.0).cloned().collect();
+	let mut groups: Vec<_> = roots.iter().filter(|root| !is_real(root) && root.sine > 0.0).map(|root| {
+		let distance = |other: &Complex| (*other - Bin(root.conj())).abs();
+		let index = (0..conjugates.len()).min_by(|a, b| distance(&conjugates[*a])
+			.total_cmp(&distance(&conjugates[*b]))).expect("complex root without a conjugate");
+		let conjugate = conjugates.swap_remove(index);
+		debug_assert!(distance(&conjugate) <= 1e-6 * root.abs().max(1.0), "mismatched conjugate pair");
+		vec![*root, conjugate]
+	}).collect();
+	assert!(conjugates.is_empty(), "complex root without a conjugate");
+	groups.extend(real.chunks(2).map(|chunk| chunk.to_vec()));
+	groups
+}
+
+/// Real coefficients of `Π(1 - root z⁻¹)` in ascending powers of `z⁻¹`
+fn polynomial(roots: &[Complex]) -> [f64; 3] {
+	assert!(roots.len() <= 2, "second order sections have at most two roots");
+	match roots {
+		[] => [1.0, 0.0, 0.0],
+		[root] => [1.0, -root.cosine, 0.0],
+		[first, second, ..] => [1.0, -(*first + *second).cosine, (*first * *second).cosine],
+	}
+}
+
+fn complex(cosine: f64, sine: f64) -> Complex {
+	Bin(Rectangular { cosine, sine })
+}
+
+fn complex_polar(magnitude: f64, phase: f64) -> Complex {
+	Bin(Polar { magnitude, phase }.into())
+}
+
+fn product(values: impl Iterator<Item=Complex>) -> Complex {
+	values.fold(complex(1.0, 0.0), |product, value| product * value)
+}
+
+fn sqrt(value: Complex) -> Complex {
+	Bin(value.powf(0.5))
+}
+
+fn cos(value: Complex) -> Complex {
+	let rotated = complex(-value.sine, value.cosine);
+	(Bin(rotated.exp()) + Bin((-rotated).exp())) / 2.0
+}
+
+fn sin(value: Complex) -> Complex {
+	let rotated = complex(-value.sine, value.cosine);
+	(Bin(rotated.exp()) - Bin((-rotated).exp())) / complex(0.0, 2.0)
+}
+
+fn acos(value: Complex) -> Complex {
+	let inner = value + complex(0.0, 1.0) * sqrt(complex(1.0, 0.0) - value * value);
+	let logarithm = complex(inner.abs().ln(), inner.sine.atan2(inner.cosine));
+	complex(0.0, -1.0) * logarithm
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SAMPLE_RATE: SampleRate = 8000;
+
+	fn decibels(gain: f64) -> f64 {
+		20.0 * gain.log10()
+	}
+
+	fn assert_stable(filter: &ZerosPolesGain) {
+		filter.poles.iter().for_each(|pole| assert!(pole.abs() < 1.0));
+	}
+
+	fn assert_sections(filter: &ZerosPolesGain) {
+		let cascade: Cascade = filter.cascade(Form::TransposedDirectTwo);
+		for frequency in [0.0, 500.0, 1000.0, 1700.0, 2500.0, 3999.0].iter() {
+			let expected = filter.gain(SAMPLE_RATE, *frequency);
+			assert!((cascade.gain(SAMPLE_RATE, *frequency) - expected).abs() < 1e-9, "{}", frequency);
+		}
+	}
+
+	fn frequencies(start: Hertz, end: Hertz) -> impl Iterator<Item=Hertz> {
+		(0..=400).map(move |index| start + (end - start) * index as f64 / 400.0)
+	}
+
+	#[test]
+	fn test_butterworth() {
+		let filter = design(Prototype::Butterworth, 5, BandType::LowPass(1000.0), SAMPLE_RATE);
+		assert_stable(&filter);
+		assert_sections(&filter);
+		let warp = |frequency: f64| (consts::PI * frequency / SAMPLE_RATE as f64).tan();
+		for frequency in frequencies(0.0, 3900.0) {
+			let expected = 1.0 / (1.0 + (warp(frequency) / warp(1000.0)).powi(10)).sqrt();
+			assert!((filter.gain(SAMPLE_RATE, frequency) - expected).abs() < 1e-9);
+		}
+	}
+
+	#[test]
+	fn test_chebyshev() {
+		for order in [3, 4].iter().cloned() {
+			let filter = design(Prototype::ChebyshevOne { ripple: 1.0 }, order, BandType::LowPass(1000.0), SAMPLE_RATE);
+			assert_stable(&filter);
+			assert_sections(&filter);
+			assert!((decibels(filter.gain(SAMPLE_RATE, 1000.0)) + 1.0).abs() < 1e-9);
+			frequencies(0.0, 1000.0).for_each(|frequency| {
+				let gain = decibels(filter.gain(SAMPLE_RATE, frequency));
+				assert!((-1.0 - 1e-9..=1e-9).contains(&gain));
+			});
+
+			let filter = design(Prototype::ChebyshevTwo { attenuation: 40.0 }, order, BandType::LowPass(1000.0), SAMPLE_RATE);
+			assert_stable(&filter);
+			assert_sections(&filter);
+			assert!((filter.gain(SAMPLE_RATE, 0.0) - 1.0).abs() < 1e-9);
+			frequencies(1000.0, 4000.0).for_each(|frequency|
+				assert!(decibels(filter.gain(SAMPLE_RATE, frequency)) <= -40.0 + 1e-9));
+		}
+	}
+
+	#[test]
+	fn test_elliptic() {
+		for order in [3, 4, 5].iter().cloned() {
+			let prototype = Prototype::Elliptic { ripple: 0.5, attenuation: 50.0 };
+			let filter = design(prototype, order, BandType::LowPass(1000.0), SAMPLE_RATE);
+			assert_stable(&filter);
+			assert_sections(&filter);
+			assert!((decibels(filter.gain(SAMPLE_RATE, 1000.0)) + 0.5).abs() < 1e-6);
+			frequencies(0.0, 1000.0).for_each(|frequency| {
+				let gain = decibels(filter.gain(SAMPLE_RATE, frequency));
+				assert!((-0.5 - 1e-6..=1e-6).contains(&gain), "{} {}", frequency, gain);
+			});
+
+			// The stopband is equiripple after the first frequency that reaches the attenuation
+			let stopband = frequencies(1000.0, 4000.0)
+				.find(|frequency| decibels(filter.gain(SAMPLE_RATE, *frequency)) <= -50.0).unwrap();
+			let peak = frequencies(stopband, 4000.0).map(|frequency| decibels(filter.gain(SAMPLE_RATE, frequency)))
+				.fold(f64::NEG_INFINITY, f64::max);
+			assert!(peak <= -50.0 + 1e-6 && peak > -50.5, "{}", peak);
+		}
+	}
+
+	#[test]
+	fn test_bessel() {
+		let prototype = analog_prototype(Prototype::Bessel, 2);
+		let pole = prototype.poles.iter().find(|pole| pole.sine > 0.0).unwrap();
+		assert!((pole.cosine + 1.1016013).abs() < 1e-6);
+		assert!((pole.sine - 0.6360098).abs() < 1e-6);
+
+		for order in 1..8 {
+			let filter = design(Prototype::Bessel, order, BandType::LowPass(500.0), SAMPLE_RATE);
+			assert_stable(&filter);
+			assert_sections(&filter);
+			assert!((filter.gain(SAMPLE_RATE, 0.0) - 1.0).abs() < 1e-9);
+			assert!((filter.gain(SAMPLE_RATE, 500.0) - consts::FRAC_1_SQRT_2).abs() < 1e-9);
+		}
+	}
+
+	#[test]
+	fn test_band_types() {
+		let half_power = consts::FRAC_1_SQRT_2;
+		let filter = design(Prototype::Butterworth, 4, BandType::HighPass(1000.0), SAMPLE_RATE);
+		assert_sections(&filter);
+		assert!(filter.gain(SAMPLE_RATE, 0.0) < 1e-9);
+		assert!((filter.gain(SAMPLE_RATE, 1000.0) - half_power).abs() < 1e-9);
+		assert!((filter.gain(SAMPLE_RATE, 4000.0) - 1.0).abs() < 1e-9);
+
+		let warp = |frequency: f64| (consts::PI * frequency / SAMPLE_RATE as f64).tan();
+		let center = (warp(1000.0) * warp(2000.0)).sqrt().atan() * SAMPLE_RATE as f64 / consts::PI;
+		let filter = design(Prototype::Butterworth, 3, BandType::BandPass(1000.0, 2000.0), SAMPLE_RATE);
+		assert_stable(&filter);
+		assert_sections(&filter);
+		assert_eq!(filter.poles.len(), 6);
+		assert!(filter.gain(SAMPLE_RATE, 0.0) < 1e-9);
+		assert!((filter.gain(SAMPLE_RATE, 1000.0) - half_power).abs() < 1e-9);
+		assert!((filter.gain(SAMPLE_RATE, center) - 1.0).abs() < 1e-9);
+		assert!((filter.gain(SAMPLE_RATE, 2000.0) - half_power).abs() < 1e-9);
+		assert!(filter.gain(SAMPLE_RATE, 4000.0) < 1e-9);
+
+		let filter = design(Prototype::ChebyshevTwo { attenuation: 30.0 }, 4, BandType::BandStop(1000.0, 2000.0), SAMPLE_RATE);
+		assert_stable(&filter);
+		assert_sections(&filter);
+		assert!((filter.gain(SAMPLE_RATE, 0.0) - 1.0).abs() < 1e-9);
+		frequencies(1000.0, 2000.0).for_each(|frequency|
+			assert!(decibels(filter.gain(SAMPLE_RATE, frequency)) <= -30.0 + 1e-9));
+		assert!((filter.gain(SAMPLE_RATE, 4000.0) - 1.0).abs() < 1e-9);
+	}
+
+	#[test]
+	fn test_cascade_impulse() {
+		let filter = design(Prototype::Elliptic { ripple: 1.0, attenuation: 40.0 }, 6, BandType::LowPass(800.0), SAMPLE_RATE);
+		let mut cascade: Cascade = filter.cascade(Form::DirectOne);
+		let mut impulse = vec![0.0; 4096];
+		impulse[0] = 1.0;
+		let response = cascade.process(&impulse);
+		let direct_gain: f64 = response.iter().sum();
+		assert!((direct_gain - filter.gain(SAMPLE_RATE, 0.0)).abs() < 1e-6);
+	}
+}
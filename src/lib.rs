@@ -1,4 +1,3 @@
-#![feature(self_struct_ctor)]
 #![feature(test)]
 
 #[cfg(feature = "fast_fourier")]
@@ -11,6 +10,7 @@ extern crate rayon;
 extern crate rustfft;
 extern crate test;
 extern crate num_cpus;
+extern crate num_traits;
 
 #[macro_use]
 pub mod type_wrapper;
@@ -32,6 +32,23 @@ pub mod window;
 pub mod short_time_fourier;
 pub mod phase_vocoder;
 pub mod bin_frequency;
+pub mod float;
+pub mod cosine_transform;
+pub mod modified_cosine;
+pub mod goertzel;
+pub mod dtmf;
+pub mod chirp_z;
+pub mod windowed_sinc;
+pub mod remez;
+pub mod biquad;
+pub mod iir_design;
+pub mod frequency_response;
+pub mod moving_average;
+pub mod single_pole;
+pub mod block_convolution;
+pub mod partitioned_convolution;
+pub mod zero_phase;
+pub mod window_metrics;
 
 #[cfg(feature = "fast_fourier")]
 pub mod fast_fourier;
@@ -1,20 +1,56 @@
-use std::f64::consts;
-use super::Sample;
+use crate::float::Float;
 
-pub fn approximate(sample: Sample) -> Sample {
+pub fn approximate<S>(sample: S) -> S where S: Float {
 	const ZERO_EPSILON: f64 = 0.0000000000001;
+	let sample = sample.into_f64();
 	if -ZERO_EPSILON < sample && sample < ZERO_EPSILON {
-		0.0
+		S::zero()
 	} else {
-		sample as f32 as f64
+		S::convert(sample as f32 as f64)
 	}
 }
 
-pub fn sinc(x: f64) -> f64 {
-	if x != 0.0 {
-		let x = x * consts::PI;
+pub fn sinc<S>(x: S) -> S where S: Float {
+	if x != S::zero() {
+		let x = x * S::PI();
 		x.sin() / x
 	} else {
-		1.0
+		S::one()
+	}
+}
+
+/// Modified Bessel function of the first kind and order zero
+pub fn bessel_zero<S>(x: S) -> S where S: Float {
+	let quarter_square = x.into_f64() * x.into_f64() / 4.0;
+	let (mut sum, mut term, mut order) = (1.0, 1.0, 1.0);
+	while term > sum * f64::EPSILON {
+		term *= quarter_square / (order * order);
+		sum += term;
+		order += 1.0;
+	}
+	S::convert(sum)
+}
+
+/// Deterministic signal of steps and a sinusoid for comparing implementations in tests
+#[cfg(test)]
+pub fn test_signal(length: usize) -> Vec<f64> {
+	(0..length).map(|x| ((x * 7) % 5) as f64 - 1.5 + (x as f64 * 0.7).sin()).collect()
+}
+
+#[cfg(test)]
+pub fn assert_samples(samples: &[f64], expected: &[f64], tolerance: f64) {
+	assert_eq!(samples.len(), expected.len());
+	for (sample, expected) in samples.iter().zip(expected.iter()) {
+		assert!((sample - expected).abs() < tolerance, "{} {}", sample, expected);
+	}
+}
+
+#[cfg(test)]
+pub fn assert_bins(bins: &[crate::bin::Bin<crate::rectangular::Rectangular>],
+                   expected: &[crate::bin::Bin<crate::rectangular::Rectangular>], tolerance: f64) {
+	assert_eq!(bins.len(), expected.len());
+	for (bin, expected) in bins.iter().zip(expected.iter()) {
+		assert!((bin.cosine - expected.cosine).abs() < tolerance, "{:?} {:?}", bin, expected);
+		assert!((bin.sine - expected.sine).abs() < tolerance, "{:?} {:?}", bin, expected);
 	}
 }
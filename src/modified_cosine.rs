@@ -0,0 +1,242 @@
+use crate::cosine_transform::{CosineKind, CosineTransform, Normalization};
+use crate::float::Float;
+use crate::window::Window;
+use rayon::prelude::*;
+use std::marker::PhantomData;
+use super::Sample;
+
+/// Folds frames into a type four discrete cosine transform
+pub struct ModifiedCosine<T, S = Sample> {
+	transform: T,
+	_sample: PhantomData<S>,
+}
+
+impl<T, S> ModifiedCosine<T, S> where T: CosineTransform<S>, S: Float {
+	/// The coefficient count must be even
+	pub fn new(coefficient_count: usize) -> Self {
+		assert!(coefficient_count > 0 && coefficient_count.is_multiple_of(2));
+		ModifiedCosine {
+			transform: T::new(CosineKind::DctIV, Normalization::Unnormalized, coefficient_count),
+			_sample: PhantomData,
+		}
+	}
+
+	pub fn coefficient_count(&self) -> usize {
+		self.transform.signal_length()
+	}
+
+	pub fn frame_length(&self) -> usize {
+		2 * self.coefficient_count()
+	}
+
+	/// Frames shorter than the frame length are padded with zeros
+	pub fn analysis(&self, frame: &[S]) -> Vec<S> {
+		let coefficient_count = self.coefficient_count();
+		let quarter = coefficient_count / 2;
+		assert!(frame.len() <= self.frame_length());
+		let sample = |index: usize| *frame.get(index).unwrap_or(&S::zero());
+
+		// Quarters (a, b, c, d) are folded into (-c' - d, a - b') where ' is reversal
+		let folded: Vec<_> = (0..coefficient_count).map(|n| if n < quarter {
+			-sample(3 * quarter - 1 - n) - sample(3 * quarter + n)
+		} else {
+			sample(n - quarter) - sample(3 * quarter - 1 - n)
+		}).collect();
+
+		let half = S::convert(0.5);
+		self.transform.transform(&folded).into_iter().map(|coefficient| coefficient * half).collect()
+	}
+
+	/// Synthesised frames contain aliasing that is cancelled by overlapping frames
+	/// Frames are scaled by `2 / N` so that windows satisfying the Princen-Bradley
+	/// condition reconstruct the signal when applied in both analysis and synthesis
+	pub fn synthesis(&self, coefficients: &[S]) -> Vec<S> {
+		let coefficient_count = self.coefficient_count();
+		let quarter = coefficient_count / 2;
+		let scale = S::one() / S::from_index(coefficient_count);
+		let folded: Vec<_> = self.transform.transform(coefficients).into_iter()
+			.map(|sample| sample * scale).collect();
+
+		// Transpose of the folding in the analysis
+		(0..self.frame_length()).map(|n| match n / quarter {
+			0 => folded[quarter + n],
+			1 | 2 => -folded[3 * quarter - 1 - n],
+			_ => -folded[n - 3 * quarter],
+		}).collect()
+	}
+}
+
+#[cfg(feature = "fast_fourier")]
+pub type FastModifiedCosine<S = Sample> = ModifiedCosine<crate::cosine_transform::FastCosine<S>, S>;
+
+/// Splits a signal into frames that overlap by half
+/// The signal is padded with zeros so that every sample is covered by two frames
+pub struct ModifiedCosineAnalyser<'a, T, S = Sample> {
+	signal: &'a [S],
+	window: &'a Window<S>,
+	transform: &'a ModifiedCosine<T, S>,
+}
+
+impl<'a, T, S> ModifiedCosineAnalyser<'a, T, S> where T: CosineTransform<S>, S: Float {
+	/// The width of the window must be the frame length of the transform
+	pub fn new(signal: &'a [S], window: &'a Window<S>, transform: &'a ModifiedCosine<T, S>) -> Self {
+		assert_eq!(transform.frame_length(), window.width());
+		ModifiedCosineAnalyser { signal, window, transform }
+	}
+
+	pub fn calculate_frame(&self, frame_index: usize) -> Vec<S> {
+		assert!(frame_index < self.total_frames());
+		let coefficient_count = self.transform.coefficient_count();
+		let frame: Vec<_> = (0..self.transform.frame_length()).map(|index| {
+			(frame_index * coefficient_count + index).checked_sub(coefficient_count)
+				.and_then(|index| self.signal.get(index)).cloned().unwrap_or_else(S::zero)
+		}).collect();
+		self.transform.analysis(&self.window.apply(&frame))
+	}
+
+	pub fn total_frames(&self) -> usize {
+		self.signal.len().div_ceil(self.transform.coefficient_count()) + 1
+	}
+
+	pub fn calculate_all(&self) -> Vec<Vec<S>> {
+		(0..self.total_frames()).into_par_iter().map(|frame_index| self.calculate_frame(frame_index)).collect()
+	}
+}
+
+/// Overlaps and adds synthesised frames to cancel their aliasing
+pub struct ModifiedCosineSynthesiser<'a, T, S = Sample> {
+	samples: Vec<S>,
+	overlap: Vec<S>,
+	window: &'a Window<S>,
+	transform: &'a ModifiedCosine<T, S>,
+	leading_padding: bool,
+}
+
+impl<'a, T, S> ModifiedCosineSynthesiser<'a, T, S> where T: CosineTransform<S>, S: Float {
+	/// The width of the window must be the frame length of the transform
+	pub fn new(window: &'a Window<S>, transform: &'a ModifiedCosine<T, S>) -> Self {
+		assert_eq!(transform.frame_length(), window.width());
+		ModifiedCosineSynthesiser {
+			samples: Vec::new(),
+			overlap: vec![S::zero(); transform.coefficient_count()],
+			window,
+			transform,
+			leading_padding: true,
+		}
+	}
+
+	pub fn push_frames(&mut self, frames: &[Vec<S>]) {
+		let (window, transform) = (self.window, self.transform);
+		let frames: Vec<_> = frames.par_iter()
+			.map(|frame| window.apply(&transform.synthesis(frame))).collect();
+
+		let coefficient_count = transform.coefficient_count();
+		for frame in frames {
+			// The first half of the first frame covers the padding before the signal
+			if !self.leading_padding {
+				self.samples.extend(self.overlap.iter().zip(&frame[..coefficient_count])
+					.map(|(overlap, sample)| *overlap + *sample));
+			}
+
+			self.leading_padding = false;
+			self.overlap.copy_from_slice(&frame[coefficient_count..]);
+		}
+	}
+
+	/// Samples that have been overlapped by both of their frames
+	pub fn flush_ready(&mut self) -> Vec<S> {
+		std::mem::take(&mut self.samples)
+	}
+
+	/// Includes the incomplete second half of the last frame
+	pub fn flush_all(mut self) -> Vec<S> {
+		self.samples.extend(self.overlap);
+		self.samples
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::cosine_transform::CorrelationCosine;
+	use crate::math;
+	use crate::window::ShiftedSine;
+	use super::*;
+
+	fn direct_analysis(frame: &[f64]) -> Vec<f64> {
+		let coefficient_count = frame.len() / 2;
+		(0..coefficient_count).map(|k| frame.iter().enumerate().map(|(n, sample)| {
+			let n = n as f64 + 0.5 + coefficient_count as f64 / 2.0;
+			sample * (std::f64::consts::PI / coefficient_count as f64 * n * (k as f64 + 0.5)).cos()
+		}).sum()).collect()
+	}
+
+	fn direct_synthesis(coefficients: &[f64]) -> Vec<f64> {
+		let coefficient_count = coefficients.len();
+		(0..2 * coefficient_count).map(|n| coefficients.iter().enumerate().map(|(k, coefficient)| {
+			let n = n as f64 + 0.5 + coefficient_count as f64 / 2.0;
+			coefficient * (std::f64::consts::PI / coefficient_count as f64 * n * (k as f64 + 0.5)).cos()
+		}).sum::<f64>() * 2.0 / coefficient_count as f64).collect()
+	}
+
+	#[test]
+	fn test_modified_cosine() {
+		for coefficient_count in [2, 4, 8, 10].iter().cloned() {
+			let frame: Vec<_> = (0..2 * coefficient_count).map(|x| ((x * 5) % 7) as f64 - 3.0).collect();
+			let transform = ModifiedCosine::<CorrelationCosine, _>::new(coefficient_count);
+			let coefficients = transform.analysis(&frame);
+			math::assert_samples(&coefficients, &direct_analysis(&frame), 1e-9);
+			math::assert_samples(&transform.synthesis(&coefficients), &direct_synthesis(&coefficients), 1e-9);
+		}
+	}
+
+	fn reconstruct<T>(signal: &[f64], window: &Window, transform: &ModifiedCosine<T>) -> Vec<f64>
+	                  where T: CosineTransform {
+		let frames = ModifiedCosineAnalyser::new(signal, window, transform).calculate_all();
+		let mut synthesiser = ModifiedCosineSynthesiser::new(window, transform);
+		synthesiser.push_frames(&frames);
+		synthesiser.flush_all()
+	}
+
+	#[test]
+	fn test_perfect_reconstruction() {
+		let signal: Vec<_> = (0..203).map(|x| (x as f64 * 0.3).sin() + ((x * 3) % 11) as f64).collect();
+		let transform = ModifiedCosine::<CorrelationCosine, _>::new(16);
+		for window in [Window::generate::<ShiftedSine>(32), Window::kaiser_bessel_derived(32, 4.0)].iter() {
+			let output = reconstruct(&signal, window, &transform);
+			assert!(output.len() >= signal.len());
+			math::assert_samples(&output[..signal.len()], &signal, 1e-9);
+		}
+	}
+
+	#[test]
+	fn test_streaming_synthesis() {
+		let signal: Vec<_> = (0..100).map(|x| x as f64).collect();
+		let window = Window::generate::<ShiftedSine>(8);
+		let transform = ModifiedCosine::<CorrelationCosine, _>::new(4);
+		let frames = ModifiedCosineAnalyser::new(&signal, &window, &transform).calculate_all();
+
+		let mut synthesiser = ModifiedCosineSynthesiser::new(&window, &transform);
+		let mut output = Vec::new();
+		for frame in frames.chunks(3) {
+			synthesiser.push_frames(frame);
+			output.extend(synthesiser.flush_ready());
+		}
+
+		assert_eq!(output.len(), signal.len());
+		math::assert_samples(&output, &signal, 1e-9);
+	}
+
+	#[test]
+	#[cfg(feature = "fast_fourier")]
+	fn test_fast_modified_cosine() {
+		let signal: Vec<_> = (0..1000).map(|x| (x as f64 * 0.01).sin()).collect();
+		let window = Window::kaiser_bessel_derived(256, 4.0);
+		let transform = FastModifiedCosine::new(128);
+		let reference = ModifiedCosine::<CorrelationCosine, _>::new(128);
+
+		let frame = &signal[..256];
+		math::assert_samples(&transform.analysis(frame), &reference.analysis(frame), 1e-9);
+		let output = reconstruct(&signal, &window, &transform);
+		math::assert_samples(&output[..signal.len()], &signal, 1e-9);
+	}
+}
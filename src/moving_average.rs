@@ -0,0 +1,144 @@
+use crate::float::Float;
+use std::collections::VecDeque;
+use super::{Hertz, Sample, SampleRate};
+
+/// Product of the width and the cutoff (as a fraction of the sample rate) where the gain is `1 / sqrt(2)`
+const HALF_POWER_WIDTH: f64 = 0.443;
+
+/// Recursive moving average that starts with a history of zeros
+/// The output is the same as a convolution with a square pulse of one over the width
+#[derive(Debug, Clone)]
+pub struct MovingAverage<S = Sample> {
+	history: VecDeque<S>,
+	sum: S,
+	processed: usize,
+}
+
+impl<S> MovingAverage<S> where S: Float {
+	pub fn new(width: usize) -> Self {
+		assert!(width > 0);
+		MovingAverage { history: vec![S::zero(); width].into(), sum: S::zero(), processed: 0 }
+	}
+
+	/// Chooses the width where the gain at the cutoff is closest to `1 / sqrt(2)`
+	pub fn from_cutoff(cutoff: Hertz, sample_rate: SampleRate) -> Self {
+		Self::new(cutoff_width(cutoff, sample_rate))
+	}
+
+	pub fn width(&self) -> usize {
+		self.history.len()
+	}
+
+	pub fn reset(&mut self) {
+		self.history.iter_mut().for_each(|sample| *sample = S::zero());
+		self.sum = S::zero();
+		self.processed = 0;
+	}
+
+	pub fn process_sample(&mut self, sample: S) -> S {
+		let oldest = self.history.pop_front().unwrap();
+		self.history.push_back(sample);
+		self.processed += 1;
+
+		// The running sum is recalculated once per width to stop rounding errors from accumulating
+		if self.processed.is_multiple_of(self.width()) {
+			self.sum = self.history.iter().cloned().sum();
+		} else {
+			self.sum += sample - oldest;
+		}
+		self.sum / S::from_index(self.width())
+	}
+
+	pub fn process(&mut self, signal: &[S]) -> Vec<S> {
+		signal.iter().map(|sample| self.process_sample(*sample)).collect()
+	}
+
+	pub fn process_in_place(&mut self, signal: &mut [S]) {
+		signal.iter_mut().for_each(|sample| *sample = self.process_sample(*sample));
+	}
+}
+
+/// Moving averages applied one after another
+/// Two passes give a triangular kernel and four passes are close to a Gaussian
+#[derive(Debug, Clone)]
+pub struct MultiPassMovingAverage<S = Sample> {
+	passes: Vec<MovingAverage<S>>,
+}
+
+impl<S> MultiPassMovingAverage<S> where S: Float {
+	pub fn new(width: usize, pass_count: usize) -> Self {
+		assert!(pass_count > 0);
+		MultiPassMovingAverage { passes: vec![MovingAverage::new(width); pass_count] }
+	}
+
+	pub fn passes(&self) -> &[MovingAverage<S>] {
+		&self.passes
+	}
+
+	pub fn reset(&mut self) {
+		self.passes.iter_mut().for_each(MovingAverage::reset);
+	}
+
+	pub fn process_sample(&mut self, sample: S) -> S {
+		self.passes.iter_mut().fold(sample, |sample, pass| pass.process_sample(sample))
+	}
+
+	pub fn process(&mut self, signal: &[S]) -> Vec<S> {
+		signal.iter().map(|sample| self.process_sample(*sample)).collect()
+	}
+
+	pub fn process_in_place(&mut self, signal: &mut [S]) {
+		signal.iter_mut().for_each(|sample| *sample = self.process_sample(*sample));
+	}
+}
+
+/// Width of a moving average with a gain of `1 / sqrt(2)` at the cutoff
+pub fn cutoff_width(cutoff: Hertz, sample_rate: SampleRate) -> usize {
+	assert!(cutoff > 0.0 && cutoff < sample_rate as f64 / 2.0);
+	let width = HALF_POWER_WIDTH * sample_rate as f64 / cutoff;
+	usize::max(width.round() as usize, 1)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::convolution;
+	use crate::filter_kernels;
+	use crate::frequency_response;
+	use crate::math::{self, test_signal};
+	use super::*;
+
+	#[test]
+	fn test_recursive_convolution() {
+		let signal = test_signal(500);
+		for width in [1, 2, 11, 64].iter().cloned() {
+			let kernel = filter_kernels::low_pass_square_pulse(1.0 / width as f64, width);
+			let expected = convolution::convolve_signal(&signal, &kernel);
+			let output = MovingAverage::new(width).process(&signal);
+			math::assert_samples(&output, &expected[..signal.len()], 1e-9);
+		}
+	}
+
+	#[test]
+	fn test_multi_pass() {
+		let signal = test_signal(300);
+		let kernel = filter_kernels::low_pass_square_pulse(1.0 / 7.0, 7);
+		let expected = (0..3).fold(signal.clone(), |signal, _| convolution::convolve_signal(&signal, &kernel));
+		let mut filter = MultiPassMovingAverage::new(7, 3);
+		let output = filter.process(&signal);
+		math::assert_samples(&output, &expected[..signal.len()], 1e-9);
+
+		filter.reset();
+		let mut streamed = signal.clone();
+		filter.process_in_place(&mut streamed);
+		math::assert_samples(&streamed, &output, 1e-9);
+	}
+
+	#[test]
+	fn test_cutoff() {
+		let filter = MovingAverage::<f64>::from_cutoff(200.0, 8000);
+		assert_eq!(filter.width(), 18);
+		let kernel = filter_kernels::low_pass_square_pulse(1.0 / filter.width() as f64, filter.width());
+		let response = frequency_response::kernel_response(&kernel, 8000, &[200.0]);
+		assert!((response.magnitudes()[0] - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-2);
+	}
+}
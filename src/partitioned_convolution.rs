@@ -0,0 +1,244 @@
+use crate::bin::Bin;
+use crate::float::Float;
+use crate::fourier_transform::FourierTransform;
+use crate::rectangular::Rectangular;
+use std::collections::VecDeque;
+use super::Sample;
+
+/// Number of partitions of each size before the size is doubled
+const PARTITIONS_PER_SIZE: usize = 2;
+
+/// Uniformly partitioned overlap save convolution of a segment of the kernel
+struct Stage<T, S> {
+	transform: T,
+	partition_length: usize,
+	partitions: Vec<Vec<Bin<Rectangular<S>>>>,
+	delay_line: VecDeque<Vec<Bin<Rectangular<S>>>>,
+	frame: Vec<S>,
+	output: VecDeque<S>,
+	offset: usize,
+}
+
+impl<T, S> Stage<T, S> where T: FourierTransform<S>, S: Float {
+	fn new(segment: &[S], partition_length: usize, offset: usize) -> Self {
+		let transform = T::new(2 * partition_length);
+		let partitions: Vec<_> = segment.chunks(partition_length).map(|partition| transform.analysis(partition)).collect();
+		let silence = vec![Bin(Rectangular { cosine: S::zero(), sine: S::zero() }); partitions[0].len()];
+		let mut stage = Stage {
+			delay_line: vec![silence; partitions.len()].into(),
+			frame: Vec::with_capacity(2 * partition_length),
+			output: VecDeque::new(),
+			transform,
+			partition_length,
+			partitions,
+			offset,
+		};
+		stage.reset();
+		stage
+	}
+
+	fn reset(&mut self) {
+		self.delay_line.iter_mut().for_each(|spectrum| spectrum.iter_mut()
+			.for_each(|bin| *bin = Bin(Rectangular { cosine: S::zero(), sine: S::zero() })));
+		self.frame.clear();
+		self.frame.resize(self.partition_length, S::zero());
+		self.output.clear();
+		self.output.resize(self.offset, S::zero());
+	}
+
+	fn push(&mut self, block: &[S]) {
+		for sample in block {
+			self.frame.push(*sample);
+			if self.frame.len() == 2 * self.partition_length {
+				self.convolve_frame();
+			}
+		}
+	}
+
+	fn convolve_frame(&mut self) {
+		self.delay_line.pop_back();
+		self.delay_line.push_front(self.transform.analysis(&self.frame));
+		let mut spectrum = vec![Bin(Rectangular { cosine: S::zero(), sine: S::zero() }); self.delay_line[0].len()];
+		for (input, partition) in self.delay_line.iter().zip(self.partitions.iter()) {
+			spectrum.iter_mut().zip(input.iter().zip(partition.iter()))
+				.for_each(|(bin, (input, partition))| *bin += *input * *partition);
+		}
+
+		let convolution = self.transform.synthesis(&spectrum);
+		self.output.extend(&convolution[self.partition_length..]);
+		self.frame.drain(..self.partition_length);
+	}
+}
+
+/// Low latency convolution that keeps the spectra of previous blocks in a frequency domain delay line
+/// The latency is the block length regardless of the length of the kernel
+pub struct PartitionedConvolver<T, S = Sample> {
+	stages: Vec<Stage<T, S>>,
+	block: Vec<S>,
+	block_length: usize,
+	kernel_length: usize,
+	has_input: bool,
+}
+
+impl<T, S> PartitionedConvolver<T, S> where T: FourierTransform<S>, S: Float {
+	/// Splits the kernel into partitions of the block length
+	pub fn uniform(kernel: &[S], block_length: usize) -> Self {
+		Self::non_uniform(kernel, block_length, block_length)
+	}
+
+	/// Uses partitions of the block length at the start of the kernel that double
+	/// in length up to the maximum partition length
+	pub fn non_uniform(kernel: &[S], block_length: usize, maximum_partition_length: usize) -> Self {
+		assert!(!kernel.is_empty() && block_length > 0);
+		assert!(maximum_partition_length >= block_length);
+		let mut stages = Vec::new();
+		let (mut offset, mut partition_length) = (0, block_length);
+		while offset < kernel.len() {
+			let is_last = 2 * partition_length > maximum_partition_length;
+			let segment_end = if is_last { kernel.len() } else {
+				usize::min(offset + PARTITIONS_PER_SIZE * partition_length, kernel.len())
+			};
+
+			// Segments start late enough that their longer partitions are ready before their output is needed
+			debug_assert!(offset + block_length >= partition_length);
+			stages.push(Stage::new(&kernel[offset..segment_end], partition_length, offset));
+			offset = segment_end;
+			partition_length *= 2;
+		}
+
+		PartitionedConvolver {
+			stages,
+			block: Vec::with_capacity(block_length),
+			block_length,
+			kernel_length: kernel.len(),
+			has_input: false,
+		}
+	}
+
+	pub fn block_length(&self) -> usize {
+		self.block_length
+	}
+
+	pub fn kernel_length(&self) -> usize {
+		self.kernel_length
+	}
+
+	/// Lengths of the partitions in each segment of the kernel
+	pub fn partition_lengths(&self) -> Vec<usize> {
+		self.stages.iter().flat_map(|stage| vec![stage.partition_length; stage.partitions.len()]).collect()
+	}
+
+	pub fn reset(&mut self) {
+		self.block.clear();
+		self.stages.iter_mut().for_each(Stage::reset);
+		self.has_input = false;
+	}
+
+	/// Returns the output of every block completed by the samples
+	pub fn process(&mut self, samples: &[S]) -> Vec<S> {
+		self.has_input |= !samples.is_empty();
+		let mut output = Vec::new();
+		let mut samples = samples;
+		while !samples.is_empty() {
+			let take = usize::min(self.block_length - self.block.len(), samples.len());
+			self.block.extend_from_slice(&samples[..take]);
+			samples = &samples[take..];
+			if self.block.len() == self.block_length {
+				output.extend(self.process_block());
+			}
+		}
+		output
+	}
+
+	/// Returns the remaining output including the tail of the kernel and resets the convolver
+	/// Nothing is returned if no input has been processed since the last reset
+	pub fn flush(&mut self) -> Vec<S> {
+		if !self.has_input {
+			return Vec::new();
+		}
+
+		let remaining = self.block.len() + self.kernel_length - 1;
+		let mut output = Vec::new();
+		while output.len() < remaining {
+			let padding = vec![S::zero(); self.block_length - self.block.len()];
+			output.extend(self.process(&padding));
+		}
+		output.truncate(remaining);
+		self.reset();
+		output
+	}
+
+	fn process_block(&mut self) -> Vec<S> {
+		let mut output = vec![S::zero(); self.block_length];
+		for stage in &mut self.stages {
+			stage.push(&self.block);
+			output.iter_mut().zip(stage.output.drain(..self.block_length))
+				.for_each(|(sample, stage_sample)| *sample += stage_sample);
+		}
+		self.block.clear();
+		output
+	}
+}
+
+#[cfg(feature = "fast_fourier")]
+pub type FastPartitionedConvolver<S = Sample> = PartitionedConvolver<crate::fast_fourier::FastFourier<S>, S>;
+
+#[cfg(test)]
+mod tests {
+	use crate::convolution;
+	use crate::fourier_transform::CorrelationFourier;
+	use crate::math::{self, test_signal};
+	use super::*;
+
+	fn convolve(convolver: &mut PartitionedConvolver<CorrelationFourier>, signal: &[f64]) -> Vec<f64> {
+		let mut output = Vec::new();
+		for chunk in signal.chunks(11) {
+			output.extend(convolver.process(chunk));
+			assert!(output.len().is_multiple_of(convolver.block_length()));
+		}
+		output.extend(convolver.flush());
+		assert!(convolver.flush().is_empty());
+		output
+	}
+
+	#[test]
+	fn test_uniform() {
+		let signal = test_signal(400);
+		for kernel_length in [1, 8, 9, 150].iter().cloned() {
+			let kernel = test_signal(kernel_length);
+			let expected = convolution::convolve_signal(&signal, &kernel);
+			let mut convolver = PartitionedConvolver::uniform(&kernel, 8);
+			assert_eq!(convolver.partition_lengths().len(), kernel_length.div_ceil(8));
+			math::assert_samples(&convolve(&mut convolver, &signal), &expected, 1e-8);
+			math::assert_samples(&convolve(&mut convolver, &signal), &expected, 1e-8);
+		}
+	}
+
+	#[test]
+	fn test_non_uniform() {
+		let signal = test_signal(500);
+		let kernel = test_signal(300);
+		let expected = convolution::convolve_signal(&signal, &kernel);
+		let mut convolver = PartitionedConvolver::non_uniform(&kernel, 4, 32);
+		assert_eq!(convolver.partition_lengths(), vec![4, 4, 8, 8, 16, 16, 32, 32, 32, 32, 32, 32, 32, 32]);
+		math::assert_samples(&convolve(&mut convolver, &signal), &expected, 1e-8);
+
+		// Output is emitted as soon as each block is complete
+		convolver.reset();
+		assert!(convolver.flush().is_empty());
+		let output = convolver.process(&signal[..4]);
+		math::assert_samples(&output, &expected[..4], 1e-8);
+	}
+
+	#[test]
+	#[cfg(feature = "fast_fourier")]
+	fn test_fast_partitioned() {
+		let signal = test_signal(8000);
+		let kernel = test_signal(3000);
+		let expected = convolution::convolve_signal(&signal, &kernel);
+		let mut convolver = FastPartitionedConvolver::non_uniform(&kernel, 64, 1024);
+		let mut output = convolver.process(&signal);
+		output.extend(convolver.flush());
+		math::assert_samples(&output, &expected, 1e-8);
+	}
+}
@@ -1,9 +1,10 @@
-use crate::bin::Bin;
-///! A phase vocoder allows pitching shifting or time scaling without
-///! changing the other domain.
-///! Adapted from http://blogs.zynaptiq.com/bernsee/pitch-shifting-using-the-ft/
+//! A phase vocoder allows pitching shifting or time scaling without
+//! changing the other domain.
+//! Adapted from http://blogs.zynaptiq.com/bernsee/pitch-shifting-using-the-ft/
 
+use crate::bin::Bin;
 use crate::bin_frequency;
+use crate::float::Float;
 use crate::fourier_transform;
 use crate::fourier_transform::FourierTransform;
 use crate::polar::Polar;
@@ -16,14 +17,16 @@ use super::Hertz;
 use super::Sample;
 use super::SampleRate;
 
+/// Frequencies are always kept in double precision as the phase
+/// accumulated from them would otherwise drift over long signals
 #[derive(Debug, Default, Copy, Clone)]
-pub struct PhaseVocoderBin {
-	pub magnitude: Sample,
+pub struct PhaseVocoderBin<S = Sample> {
+	pub magnitude: S,
 	pub frequency: Hertz,
 }
 
-pub trait PhaseVocoderProcessor: Send + Sync {
-	fn process(&self, bins: Vec<PhaseVocoderBin>, output_bins: &mut Vec<PhaseVocoderBin>);
+pub trait PhaseVocoderProcessor<S = Sample>: Send + Sync {
+	fn process(&self, bins: Vec<PhaseVocoderBin<S>>, output_bins: &mut Vec<PhaseVocoderBin<S>>);
 }
 
 #[derive(Debug, Clone)]
@@ -31,8 +34,8 @@ pub struct PitchShift {
 	pub pitch_shift_ratio: f64,
 }
 
-impl PhaseVocoderProcessor for PitchShift {
-	fn process(&self, bins: Vec<PhaseVocoderBin>, output_bins: &mut Vec<PhaseVocoderBin>) {
+impl<S> PhaseVocoderProcessor<S> for PitchShift where S: Float {
+	fn process(&self, bins: Vec<PhaseVocoderBin<S>>, output_bins: &mut Vec<PhaseVocoderBin<S>>) {
 		for bin_index in 0..bins.len() {
 			let pitch_shifted_index = (bin_index as f64 * self.pitch_shift_ratio) as i64;
 			if 0 <= pitch_shifted_index && pitch_shifted_index < bins.len() as i64 {
@@ -46,27 +49,29 @@ impl PhaseVocoderProcessor for PitchShift {
 
 pub struct IdentityProcessor();
 
-impl PhaseVocoderProcessor for IdentityProcessor {
-	fn process(&self, bins: Vec<PhaseVocoderBin>, output_bins: &mut Vec<PhaseVocoderBin>) {
-		use std::mem;
-		mem::replace(output_bins, bins);
+impl<S> PhaseVocoderProcessor<S> for IdentityProcessor where S: Float {
+	fn process(&self, bins: Vec<PhaseVocoderBin<S>>, output_bins: &mut Vec<PhaseVocoderBin<S>>) {
+		*output_bins = bins;
 	}
 }
 
-pub fn process_signal<T, P>(signal: &[Sample], sample_rate: SampleRate, overlap: usize, window: &Window, processor: P)
-                            -> Vec<Sample> where T: FourierTransform, P: PhaseVocoderProcessor {
+/// The transform must be planned for the width of the window
+pub fn process_signal<S, T, P>(transform: &T, signal: &[S], sample_rate: SampleRate, overlap: usize,
+                               window: &Window<S>, processor: P) -> Vec<S>
+                               where S: Float, T: FourierTransform<S>, P: PhaseVocoderProcessor<S> {
 	let chunk_size = ::num_cpus::get() * 128;
 	let frame_step_size = window.width() - overlap;
 	let phase_step = phase_step(frame_step_size, window);
 	let bin_count = fourier_transform::bin_count(window.width());
 	let bin_width = bin_frequency::bin_width(sample_rate, bin_count);
 	let overlap_factor = overlap_factor(overlap, window);
-	let analyser = short_time_fourier::ShortTimeAnalyser::<T>::new(signal, overlap, window);
+	let analyser = short_time_fourier::ShortTimeAnalyser::new(signal, overlap, window, transform);
 
 	let mut bin_phase_accumulate = vec![0.0; bin_count];
 	let mut previous_chunk_frame = vec![Bin(Polar::default()); bin_count];
-	let window = &window.normalize_amplitude(overlap_factor);
-	let mut synthesiser = short_time_fourier::ShortTimeSynthesiser::<T>::new(overlap, window);
+	let normalisation = short_time_fourier::Normalisation::WeightedOverlapAdd;
+	let mut synthesiser = short_time_fourier::ShortTimeSynthesiser::with_normalisation(overlap, window,
+		transform, normalisation);
 	let mut samples = Vec::new();
 
 	let mut chunk_frame_index = 0;
@@ -97,17 +102,17 @@ pub fn process_signal<T, P>(signal: &[Sample], sample_rate: SampleRate, overlap:
 			output_bins
 		}).collect();
 
-		let frames = vec![vec![Bin(Rectangular::default()); bin_count]; index_end];
-		bin_phase_accumulate.par_iter_mut().enumerate().for_each(|(bin_index, accumulate)| {
-			for (frame_index, frame) in processed_frames.iter().enumerate() {
+		let bin_columns: Vec<Vec<_>> = bin_phase_accumulate.par_iter_mut().enumerate()
+			.map(|(bin_index, accumulate)| processed_frames.iter().map(|frame| {
 				let bin = &frame[bin_index];
 				let phase = original_phase(overlap_factor, phase_step, bin_width, bin_index,
 				                           bin.frequency, *accumulate);
-				let pointer = &frames[frame_index][bin_index] as *const _ as *mut Bin<Rectangular>;
-				unsafe { *pointer = Bin(Polar { magnitude: bin.magnitude, phase }.into()); }
 				*accumulate = phase;
-			}
-		});
+				Bin(Rectangular::from(Polar { magnitude: bin.magnitude, phase: S::convert(phase) }))
+			}).collect()).collect();
+
+		let frames: Vec<Vec<_>> = (0..index_end).map(|frame_index| bin_columns.iter()
+			.map(|column| column[frame_index]).collect()).collect();
 		synthesiser.push_frames(&frames);
 
 		samples.append(&mut synthesiser.flush_ready());
@@ -119,20 +124,20 @@ pub fn process_signal<T, P>(signal: &[Sample], sample_rate: SampleRate, overlap:
 	samples
 }
 
-pub fn overlap_factor(overlap: usize, window: &Window) -> f64 {
+pub fn overlap_factor<S>(overlap: usize, window: &Window<S>) -> f64 where S: Float {
 	window.width() as f64 / (window.width() - overlap) as f64
 }
 
 /// Constant factor for converting phase angles to phase shift in samples
-pub fn phase_step(frame_step_size: usize, window: &Window) -> f64 {
+pub fn phase_step<S>(frame_step_size: usize, window: &Window<S>) -> f64 where S: Float {
 	2.0 * consts::PI * (frame_step_size as f64 / window.width() as f64)
 }
 
 /// Estimates the true frequency of a bin by analysing the change in phase
 /// of a bin across each frame
-pub fn true_frequency(phase_step: f64, bin_width: f64, bin_index: usize,
-                      bin: &Bin<Polar>, previous_frame_bin: &Bin<Polar>) -> Hertz {
-	let phase_difference = bin.phase - previous_frame_bin.phase;
+pub fn true_frequency<S>(phase_step: f64, bin_width: f64, bin_index: usize,
+                         bin: &Bin<Polar<S>>, previous_frame_bin: &Bin<Polar<S>>) -> Hertz where S: Float {
+	let phase_difference = bin.phase.into_f64() - previous_frame_bin.phase.into_f64();
 	let expected_phase_difference = bin_index as f64 * phase_step;
 	let actual_phase_difference = phase_difference - expected_phase_difference;
 
@@ -169,7 +174,7 @@ mod tests {
 
 	#[test]
 	fn test_overlap_factor() {
-		let window = Window::generate::<window::Dirichlet>(512);
+		let window: Window = Window::generate::<window::Dirichlet>(512);
 		assert_eq!(overlap_factor(256, &window), 2.0);
 		assert_eq!(overlap_factor(384, &window), 4.0);
 	}
@@ -177,7 +182,7 @@ mod tests {
 	#[test]
 	fn test_phase_step() {
 		let frame_step_size = 256;
-		let window = Window::generate::<window::Dirichlet>(512);
+		let window: Window = Window::generate::<window::Dirichlet>(512);
 		assert_eq!(phase_step(frame_step_size, &window), consts::PI);
 	}
 
@@ -185,7 +190,7 @@ mod tests {
 	fn test_true_frequency() {
 		let sample_rate = 44100;
 		let overlap = 256;
-		let window = Window::generate::<window::Dirichlet>(512);
+		let window: Window = Window::generate::<window::Dirichlet>(512);
 
 		let frame_step_size = window.width() - overlap;
 		let bin_count = fourier_transform::bin_count(window.width());
@@ -209,7 +214,7 @@ mod tests {
 	fn test_original_phase() {
 		let sample_rate = 44100;
 		let overlap = 256;
-		let window = Window::generate::<window::Dirichlet>(512);
+		let window: Window = Window::generate::<window::Dirichlet>(512);
 		let overlap_factor = overlap_factor(overlap, &window);
 
 		let frame_step_size = window.width() - overlap;
@@ -228,6 +233,32 @@ mod tests {
 		                          true_frequency, accumulate_phase), 191.54073831888627);
 	}
 
+	#[test]
+	#[cfg(feature = "fast_fourier")]
+	fn test_single_precision() {
+		use crate::fast_fourier::FastFourier;
+		let sample_rate = 44100;
+		let overlap = 192;
+		let processor = PitchShift { pitch_shift_ratio: 1.5 };
+		let signal: Vec<f64> = (0..8192).map(|x| {
+			(2.0 * consts::PI * 440.0 * (x as f64 / sample_rate as f64)).sin() * 10000.0
+		}).collect();
+		let single_signal: Vec<f32> = signal.iter().map(|x| *x as f32).collect();
+
+		let window: Window<f64> = Window::generate::<window::Hann>(256);
+		let double = process_signal(&FastFourier::new(256), &signal, sample_rate, overlap, &window, processor.clone());
+		let window: Window<f32> = Window::generate::<window::Hann>(256);
+		let single = process_signal(&FastFourier::new(256), &single_signal, sample_rate, overlap, &window, processor);
+
+		// Single precision stays within a 16 bit quantisation step but is far from exact
+		let peak = double.iter().fold(0.0, |peak: f64, x| peak.max(x.abs()));
+		let error = double.iter().zip(single.iter())
+		                  .map(|(double, single)| (double - *single as f64).abs())
+		                  .fold(0.0, f64::max);
+		assert!(error / peak < 1.0 / 32768.0);
+		assert!(error / peak > 1e-7);
+	}
+
 	#[bench]
 	#[cfg(feature = "fast_fourier")]
 	fn bench_pitch_shifter(bench: &mut Bencher) {
@@ -237,7 +268,8 @@ mod tests {
 		let window = Window::generate::<window::Sine>(256);
 		let processor = PitchShift { pitch_shift_ratio: 1.5 };
 		let signal: Vec<_> = (0..22050).map(|x| x as f64).collect();
-		bench.iter(|| process_signal::<FastFourier, _>(&signal, sample_rate, overlap,
-		                                               &window, processor.clone()));
+		let transform = FastFourier::new(window.width());
+		bench.iter(|| process_signal(&transform, &signal, sample_rate, overlap,
+		                             &window, processor.clone()));
 	}
 }
@@ -1,37 +1,76 @@
 use crate::bin::Bin;
+use crate::float::Float;
 use crate::rectangular::Rectangular;
-use std::f64::consts;
 use super::Sample;
 
 #[derive(Debug, Default, PartialOrd, PartialEq, Copy, Clone)]
-pub struct Polar {
-	pub magnitude: Sample,
-	pub phase: Sample,
+pub struct Polar<S = Sample> {
+	pub magnitude: S,
+	pub phase: S,
 }
 
-impl Polar {
-	/// Allow the magnitude to be negative to resolve cutting of the phase
-	pub fn unwrap_phase(&mut self, previous_phase: Sample) {
-		let multiplier = (previous_phase - self.phase) / (2.0 * consts::PI);
-		self.phase += multiplier.trunc() * 2.0 * consts::PI;
+impl<S> Polar<S> where S: Float {
+	/// Shifts the phase by whole turns so that it is within half a turn of the previous phase
+	pub fn unwrap_phase(&mut self, previous_phase: S) {
+		let multiplier = (previous_phase - self.phase) / S::TAU();
+		self.phase += multiplier.round() * S::TAU();
 	}
 
-	pub fn complex_conjugate(&self) -> Polar {
+	pub fn complex_conjugate(&self) -> Polar<S> {
 		Polar {
 			magnitude: self.magnitude,
 			phase: -self.phase,
 		}
 	}
+
+	pub fn conj(&self) -> Polar<S> {
+		self.complex_conjugate()
+	}
+
+	/// Squared magnitude of the complex number
+	pub fn norm_sqr(&self) -> S {
+		self.magnitude * self.magnitude
+	}
+
+	/// Magnitude of the complex number
+	pub fn norm(&self) -> S {
+		self.magnitude.abs()
+	}
+
+	/// Magnitude of the complex number
+	pub fn abs(&self) -> S {
+		self.norm()
+	}
+
+	pub fn exp(&self) -> Polar<S> {
+		let rectangular = Rectangular::from(*self);
+		Polar {
+			magnitude: rectangular.cosine.exp(),
+			phase: rectangular.sine,
+		}
+	}
+
+	pub fn powf(&self, exponent: S) -> Polar<S> {
+		let (magnitude, phase) = match self.magnitude < S::zero() {
+			true => (-self.magnitude, self.phase + S::PI()),
+			false => (self.magnitude, self.phase),
+		};
+
+		Polar {
+			magnitude: magnitude.powf(exponent),
+			phase: phase * exponent,
+		}
+	}
 }
 
-impl From<Rectangular> for Polar {
-	fn from(other: Rectangular) -> Self {
+impl<S> From<Rectangular<S>> for Polar<S> where S: Float {
+	fn from(other: Rectangular<S>) -> Self {
 		let magnitude = (other.cosine * other.cosine + other.sine * other.sine).sqrt();
-		let phase = if other.cosine == 0.0 {
+		let phase = if other.cosine == S::zero() {
 			if other.sine.is_sign_positive() {
-				consts::FRAC_PI_2
+				S::FRAC_PI_2()
 			} else {
-				-consts::FRAC_PI_2
+				-S::FRAC_PI_2()
 			}
 		} else {
 			other.sine.atan2(other.cosine)
@@ -40,10 +79,10 @@ impl From<Rectangular> for Polar {
 	}
 }
 
-pub fn to_polar_spectrum(bins: &[Bin<Rectangular>]) -> Vec<Bin<Polar>> {
+pub fn to_polar_spectrum<S>(bins: &[Bin<Rectangular<S>>]) -> Vec<Bin<Polar<S>>> where S: Float {
 	let mut previous_phase = None;
 	bins.iter().map(|bin| {
-		let mut polar: Bin<Polar> = (*bin).into();
+		let mut polar: Bin<Polar<S>> = (*bin).into();
 		if let Some(previous_phase) = previous_phase {
 			polar.unwrap_phase(previous_phase);
 		}
@@ -55,6 +94,7 @@ pub fn to_polar_spectrum(bins: &[Bin<Rectangular>]) -> Vec<Bin<Polar>> {
 #[cfg(test)]
 mod tests {
 	use crate::math;
+	use std::f64::consts;
 	use super::*;
 
 	#[test]
@@ -70,4 +110,21 @@ mod tests {
 		let polar: Polar = Rectangular { cosine: -5.0, sine: 0.0 }.into();
 		assert_eq!(polar.phase, consts::PI);
 	}
+
+	#[test]
+	fn test_norm() {
+		let polar = Polar { magnitude: -2.0, phase: 1.0 };
+		assert_eq!(polar.norm(), 2.0);
+		assert_eq!(polar.norm_sqr(), 4.0);
+		assert_eq!(polar.abs(), 2.0);
+	}
+
+	#[test]
+	fn test_unwrap_phase() {
+		let mut polar = Polar { magnitude: 1.0, phase: -3.0 };
+		polar.unwrap_phase(3.0);
+		assert!((polar.phase - (consts::TAU - 3.0)).abs() < 1e-12);
+		polar.unwrap_phase(-20.0);
+		assert!((polar.phase - (-3.0 - 3.0 * consts::TAU)).abs() < 1e-12);
+	}
 }
@@ -1,17 +1,113 @@
+use crate::float::Float;
 use crate::polar::Polar;
+#[cfg(feature = "fast_fourier")]
+use num_complex::Complex;
 use super::Sample;
 
 #[derive(Debug, Default, PartialOrd, PartialEq, Copy, Clone)]
-pub struct Rectangular {
-	pub cosine: Sample,
-	pub sine: Sample,
+pub struct Rectangular<S = Sample> {
+	pub cosine: S,
+	pub sine: S,
 }
 
-impl From<Polar> for Rectangular {
-	fn from(other: Polar) -> Self {
+impl<S> Rectangular<S> where S: Float {
+	pub fn conj(&self) -> Rectangular<S> {
+		Rectangular {
+			cosine: self.cosine,
+			sine: -self.sine,
+		}
+	}
+
+	/// Squared magnitude of the complex number
+	pub fn norm_sqr(&self) -> S {
+		self.cosine * self.cosine + self.sine * self.sine
+	}
+
+	/// Magnitude of the complex number
+	pub fn norm(&self) -> S {
+		self.cosine.hypot(self.sine)
+	}
+
+	/// Magnitude of the complex number
+	pub fn abs(&self) -> S {
+		self.norm()
+	}
+
+	pub fn exp(&self) -> Rectangular<S> {
+		Polar { magnitude: self.cosine.exp(), phase: self.sine }.into()
+	}
+
+	pub fn powf(&self, exponent: S) -> Rectangular<S> {
+		Polar::from(*self).powf(exponent).into()
+	}
+}
+
+impl<S> From<Polar<S>> for Rectangular<S> where S: Float {
+	fn from(other: Polar<S>) -> Self {
 		Rectangular {
 			cosine: other.magnitude * other.phase.cos(),
 			sine: other.magnitude * other.phase.sin(),
 		}
 	}
 }
+
+#[cfg(feature = "fast_fourier")]
+impl<S> From<Complex<S>> for Rectangular<S> {
+	fn from(other: Complex<S>) -> Self {
+		Rectangular {
+			cosine: other.re,
+			sine: other.im,
+		}
+	}
+}
+
+#[cfg(feature = "fast_fourier")]
+impl<S> From<Rectangular<S>> for Complex<S> {
+	fn from(other: Rectangular<S>) -> Self {
+		Complex::new(other.cosine, other.sine)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_conj() {
+		let rectangular = Rectangular { cosine: 3.0, sine: 4.0 };
+		assert_eq!(rectangular.conj(), Rectangular { cosine: 3.0, sine: -4.0 });
+		assert_eq!(rectangular.norm_sqr(), 25.0);
+		assert_eq!(rectangular.norm(), 5.0);
+		assert_eq!(rectangular.abs(), 5.0);
+	}
+
+	#[test]
+	fn test_exp() {
+		use crate::math;
+		use std::f64::consts;
+		let exponential = Rectangular { cosine: 0.0, sine: consts::PI }.exp();
+		assert_eq!(math::approximate(exponential.cosine), -1.0);
+		assert_eq!(math::approximate(exponential.sine), 0.0);
+
+		let exponential = Rectangular { cosine: 2.0, sine: 0.0 }.exp();
+		assert_eq!(exponential.cosine, 2.0_f64.exp());
+	}
+
+	#[test]
+	fn test_powf() {
+		use crate::math;
+		let square = Rectangular { cosine: 1.0, sine: 2.0 }.powf(2.0);
+		assert_eq!(math::approximate(square.cosine), -3.0);
+		assert_eq!(math::approximate(square.sine), 4.0);
+	}
+
+	#[test]
+	#[cfg(feature = "fast_fourier")]
+	fn test_complex_conversion() {
+		use num_complex::Complex64;
+		let rectangular = Rectangular { cosine: 0.1, sine: -1.0 / 3.0 };
+		let complex: Complex64 = rectangular.into();
+		assert_eq!(complex, Complex64::new(0.1, -1.0 / 3.0));
+		assert_eq!(Rectangular::from(complex), rectangular);
+	}
+}
@@ -0,0 +1,375 @@
+use crate::float::Float;
+use std::f64::consts;
+
+const GRID_DENSITY: usize = 32;
+const REPORT_DENSITY: usize = 8;
+const MAXIMUM_ITERATIONS: usize = 100;
+const CONVERGENCE_TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone)]
+pub struct Band {
+	pub start: f64,
+	pub end: f64,
+	pub gain: f64,
+	/// Relative importance of the error within the band
+	pub weight: f64,
+}
+
+impl Band {
+	pub fn new(start: f64, end: f64, gain: f64, weight: f64) -> Self {
+		Band { start, end, gain, weight }
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct EquirippleDesign<S> {
+	pub kernel: Vec<S>,
+	/// Largest weighted error over every band
+	pub deviation: f64,
+	/// Largest unweighted error within each band
+	pub band_deviations: Vec<f64>,
+	pub iterations: usize,
+	/// Whether the errors at the extremal frequencies became equal
+	pub converged: bool,
+}
+
+impl<S> EquirippleDesign<S> {
+	/// Checks that the error within each band does not exceed its tolerance
+	pub fn within_tolerances(&self, tolerances: &[f64]) -> bool {
+		assert_eq!(tolerances.len(), self.band_deviations.len());
+		self.band_deviations.iter().zip(tolerances).all(|(deviation, tolerance)| deviation <= tolerance)
+	}
+}
+
+/// Converts a passband deviation into peak to peak ripple in decibels
+pub fn passband_ripple_decibels(deviation: f64) -> f64 {
+	20.0 * ((1.0 + deviation) / (1.0 - deviation)).log10()
+}
+
+/// Converts a stopband deviation into attenuation in decibels
+pub fn stopband_attenuation_decibels(deviation: f64) -> f64 {
+	-20.0 * deviation.log10()
+}
+
+/// Estimates the length of a low pass kernel that meets the deviations
+/// with a transition width as a fraction of the sample rate (Herrmann et al.)
+pub fn estimate_length(passband_deviation: f64, stopband_deviation: f64, transition_width: f64) -> usize {
+	assert!(transition_width > 0.0 && transition_width < 0.5);
+	let (passband, stopband) = (passband_deviation.log10(), stopband_deviation.log10());
+	let asymptote = (5.309e-3 * passband * passband + 7.114e-2 * passband - 4.761e-1) * stopband
+		- (2.66e-3 * passband * passband + 5.941e-1 * passband + 4.278e-1);
+	let correction = 11.01217 + 0.51244 * (passband - stopband);
+	let length = asymptote / transition_width - correction * transition_width + 1.0;
+	length.ceil().max(1.0) as usize
+}
+
+struct GridPoint {
+	frequency: f64,
+	gain: f64,
+	weight: f64,
+	band: usize,
+}
+
+/// Designs a symmetric kernel of the given length with the Parks-McClellan algorithm
+/// Band edges are fractions of the sample rate between zero and one half
+/// Kernels of even length always have zero gain at the Nyquist frequency
+pub fn design<S>(length: usize, bands: &[Band]) -> EquirippleDesign<S> where S: Float {
+	assert!(length > 1);
+	assert!(!bands.is_empty());
+	for (index, band) in bands.iter().enumerate() {
+		assert!(0.0 <= band.start && band.start <= band.end && band.end <= 0.5);
+		assert!(band.weight > 0.0);
+		if index > 0 {
+			assert!(bands[index - 1].end < band.start);
+		}
+	}
+
+	// Kernels of even length contain a factor of cos(πf) which is removed from the approximation
+	let even = length.is_multiple_of(2);
+	let basis_count = length.div_ceil(2);
+	let factor = |frequency: f64| if even { (consts::PI * frequency).cos() } else { 1.0 };
+
+	// Grid points are spread over the bands in proportion to their widths
+	let total_width: f64 = bands.iter().map(|band| band.end - band.start).sum();
+	assert!(total_width > 0.0, "bands must not all be single frequencies");
+	let spacing = total_width / (GRID_DENSITY * basis_count) as f64;
+	let mut grid = Vec::new();
+	for (index, band) in bands.iter().enumerate() {
+		let end = if even { band.end.min(0.5 - spacing) } else { band.end };
+		let count = (((end - band.start) / spacing).round() as usize).max(1);
+		grid.extend((0..=count).map(|point| {
+			let frequency = band.start + (end - band.start) * point as f64 / count as f64;
+			let factor = factor(frequency);
+			GridPoint { frequency, gain: band.gain / factor, weight: band.weight * factor, band: index }
+		}));
+	}
+	debug_assert!(grid.len() > basis_count);
+
+	let mut extremals: Vec<usize> = (0..=basis_count)
+		.map(|index| index * (grid.len() - 1) / basis_count).collect();
+	let mut approximation = Approximation::new(&grid, &extremals);
+	let (mut iterations, mut converged) = (0, false);
+	while iterations < MAXIMUM_ITERATIONS {
+		iterations += 1;
+		let errors: Vec<_> = grid.iter().map(|point|
+			point.weight * (point.gain - approximation.evaluate(point.frequency))).collect();
+		let maximum_error = errors.iter().fold(0.0_f64, |maximum, error| maximum.max(error.abs()));
+		if maximum_error - approximation.deviation.abs() <= CONVERGENCE_TOLERANCE * maximum_error {
+			converged = true;
+			break;
+		}
+
+		match find_extremals(&grid, &errors, basis_count + 1) {
+			Some(next) if next != extremals => extremals = next,
+			_ => break,
+		}
+		approximation = Approximation::new(&grid, &extremals);
+	}
+
+	// Samples the amplitude response to recover the kernel (frequency sampling)
+	let center = (length - 1) as f64 / 2.0;
+	let amplitudes: Vec<_> = (0..length.div_ceil(2))
+		.map(|k| k as f64 / length as f64)
+		.map(|frequency| approximation.evaluate(frequency) * factor(frequency)).collect();
+	let kernel: Vec<f64> = (0..length).map(|n| {
+		amplitudes.iter().enumerate().map(|(k, amplitude)| {
+			let scale = if k == 0 { 1.0 } else { 2.0 };
+			scale * amplitude * (2.0 * consts::PI * k as f64 * (n as f64 - center) / length as f64).cos()
+		}).sum::<f64>() / length as f64
+	}).collect();
+
+	// Errors are reported over a finer grid as they can peak between points of the design grid
+	let mut band_deviations = vec![0.0_f64; bands.len()];
+	let mut deviation = 0.0_f64;
+	for (index, band) in bands.iter().enumerate() {
+		let count = (((band.end - band.start) / spacing).round() as usize * REPORT_DENSITY).max(1);
+		for point in 0..=count {
+			let frequency = band.start + (band.end - band.start) * point as f64 / count as f64;
+			let error = (band.gain - amplitude_response(&kernel, frequency)).abs();
+			band_deviations[index] = band_deviations[index].max(error);
+			deviation = deviation.max(band.weight * error);
+		}
+	}
+
+	EquirippleDesign {
+		kernel: kernel.into_iter().map(S::convert).collect(),
+		deviation,
+		band_deviations,
+		iterations,
+		converged,
+	}
+}
+
+/// Real valued gain of a symmetric kernel after removing its linear phase
+pub fn amplitude_response(kernel: &[f64], frequency: f64) -> f64 {
+	let center = (kernel.len() - 1) as f64 / 2.0;
+	kernel.iter().enumerate().map(|(n, sample)|
+		sample * (2.0 * consts::PI * frequency * (n as f64 - center)).cos()).sum()
+}
+
+/// Polynomial in `cos(2πf)` that alternately deviates from the desired gain at the extremals
+struct Approximation {
+	abscissae: Vec<f64>,
+	weights: Vec<f64>,
+	values: Vec<f64>,
+	deviation: f64,
+}
+
+impl Approximation {
+	fn new(grid: &[GridPoint], extremals: &[usize]) -> Self {
+		let abscissae: Vec<_> = extremals.iter()
+			.map(|index| (2.0 * consts::PI * grid[*index].frequency).cos()).collect();
+		let weights: Vec<_> = abscissae.iter().enumerate().map(|(index, abscissa)| {
+			1.0 / abscissae.iter().enumerate().filter(|(other, _)| *other != index)
+				.map(|(_, other)| 2.0 * (abscissa - other)).product::<f64>()
+		}).collect();
+
+		let sign = |index: usize| if index.is_multiple_of(2) { 1.0 } else { -1.0 };
+		let numerator: f64 = extremals.iter().zip(&weights).map(|(index, weight)| weight * grid[*index].gain).sum();
+		let denominator: f64 = extremals.iter().zip(&weights).enumerate()
+			.map(|(order, (index, weight))| sign(order) * weight / grid[*index].weight).sum();
+		let deviation = numerator / denominator;
+
+		let values = extremals.iter().enumerate().map(|(order, index)|
+			grid[*index].gain - sign(order) * deviation / grid[*index].weight).collect();
+		Approximation { abscissae, weights, values, deviation }
+	}
+
+	/// Barycentric interpolation through the values at the extremals
+	fn evaluate(&self, frequency: f64) -> f64 {
+		let abscissa = (2.0 * consts::PI * frequency).cos();
+		let (mut numerator, mut denominator) = (0.0, 0.0);
+		for ((other, weight), value) in self.abscissae.iter().zip(&self.weights).zip(&self.values) {
+			let difference = abscissa - other;
+			if difference.abs() < 1e-14 {
+				return *value;
+			}
+			numerator += weight / difference * value;
+			denominator += weight / difference;
+		}
+		numerator / denominator
+	}
+}
+
+/// Locates alternating local extrema of the error and reduces them to the required count
+fn find_extremals(grid: &[GridPoint], errors: &[f64], count: usize) -> Option<Vec<usize>> {
+	let mut candidates: Vec<usize> = Vec::new();
+	for (index, error) in errors.iter().enumerate() {
+		let neighbour = |other: Option<usize>| other
+			.filter(|other| grid.get(*other).is_some_and(|other| other.band == grid[index].band))
+			.map(|other| &errors[other]);
+		let (previous, next) = (neighbour(index.checked_sub(1)), neighbour(Some(index + 1)));
+		let extremum = |compare: fn(&f64, &f64) -> bool| previous.is_none_or(|previous| compare(error, previous)) &&
+			next.is_none_or(|next| compare(error, next));
+		if (*error > 0.0 && extremum(|a, b| a >= b)) || (*error < 0.0 && extremum(|a, b| a <= b)) {
+			match candidates.last() {
+				Some(last) if errors[*last].signum() == error.signum() => {
+					if error.abs() > errors[*last].abs() {
+						*candidates.last_mut().unwrap() = index;
+					}
+				}
+				_ => candidates.push(index),
+			}
+		}
+	}
+
+	// Removing either end preserves the alternation of the remaining extremals
+	while candidates.len() > count {
+		let (first, last) = (candidates[0], candidates[candidates.len() - 1]);
+		if errors[first].abs() < errors[last].abs() {
+			candidates.remove(0);
+		} else {
+			candidates.pop();
+		}
+	}
+
+	if candidates.len() == count { Some(candidates) } else { None }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn low_pass_bands(passband: f64, stopband: f64, stopband_weight: f64) -> Vec<Band> {
+		vec![Band::new(0.0, passband, 1.0, 1.0), Band::new(stopband, 0.5, 0.0, stopband_weight)]
+	}
+
+	#[test]
+	fn test_three_taps() {
+		// The alternation theorem gives a closed form solution for a single cosine
+		let design = design::<f64>(3, &low_pass_bands(0.1, 0.4, 1.0));
+		let outer = 1.0 / (2.0 + 2.0 * (consts::PI / 5.0).cos());
+		assert!(design.converged);
+		assert!((design.kernel[0] - outer).abs() < 1e-9);
+		assert!((design.kernel[1] - 0.5).abs() < 1e-9);
+		assert!((design.kernel[2] - outer).abs() < 1e-9);
+		assert!((design.deviation - (2.0 * outer - 0.5)).abs() < 1e-9);
+	}
+
+	#[test]
+	fn test_chebyshev_reference() {
+		// A single passband frequency with an equiripple stopband is solved by a Chebyshev
+		// polynomial mapped onto the stopband (Helms 1971, the Dolph-Chebyshev design)
+		let (length, stopband) = (21, 0.2);
+		let order = (length - 1) / 2;
+		let edge = (2.0 * consts::PI * stopband).cos();
+		let map = |x: f64| (2.0 * x + 1.0 - edge) / (edge + 1.0);
+		let chebyshev = |x: f64| if x.abs() <= 1.0 { (order as f64 * x.acos()).cos() } else {
+			(order as f64 * x.acosh()).cosh()
+		};
+		let deviation = 1.0 / (1.0 + chebyshev(map(1.0)));
+		let amplitude = |frequency: f64| deviation * chebyshev(map((2.0 * consts::PI * frequency).cos()));
+		let center = order as f64;
+		let expected: Vec<f64> = (0..length).map(|n| (0..=order).map(|k| {
+			let scale = if k == 0 { 1.0 } else { 2.0 };
+			let phase = 2.0 * consts::PI * k as f64 * (n as f64 - center) / length as f64;
+			scale * amplitude(k as f64 / length as f64) * phase.cos()
+		}).sum::<f64>() / length as f64).collect();
+
+		let bands = [Band::new(0.0, 0.0, 1.0, 1.0), Band::new(stopband, 0.5, 0.0, 1.0)];
+		let design = design::<f64>(length, &bands);
+		assert!(design.converged);
+		assert!((design.deviation - deviation).abs() < 1e-6, "{} {}", design.deviation, deviation);
+		for (sample, expected) in design.kernel.iter().zip(expected.iter()) {
+			assert!((sample - expected).abs() < 1e-5, "{} {}", sample, expected);
+		}
+	}
+
+	fn assert_equiripple(design: &EquirippleDesign<f64>, bands: &[Band]) {
+		assert!(design.converged);
+		let kernel = &design.kernel;
+		kernel.iter().zip(kernel.iter().rev()).for_each(|(a, b)| assert!((a - b).abs() < 1e-12));
+
+		// Counts the alternations of the weighted error over a fine grid
+		let mut alternations = Vec::new();
+		for band in bands {
+			for point in 0..=2000 {
+				let frequency = band.start + (band.end - band.start) * point as f64 / 2000.0;
+				let error = band.weight * (band.gain - amplitude_response(kernel, frequency));
+				assert!(error.abs() <= design.deviation * (1.0 + 1e-4));
+				if error.abs() > design.deviation * (1.0 - 1e-2) {
+					match alternations.last() {
+						Some(last) if *last == error.signum() => (),
+						_ => alternations.push(error.signum()),
+					}
+				}
+			}
+		}
+		assert!(alternations.len() > kernel.len().div_ceil(2), "{}", alternations.len());
+	}
+
+	#[test]
+	fn test_low_pass() {
+		let bands = low_pass_bands(0.2, 0.25, 1.0);
+		assert_equiripple(&design(31, &bands), &bands);
+		assert_equiripple(&design(30, &bands), &bands);
+	}
+
+	#[test]
+	fn test_narrow_bands() {
+		// A grid spread over the whole spectrum would have fewer points in the bands than coefficients
+		let bands = [Band::new(0.1, 0.11, 1.0, 1.0), Band::new(0.3, 0.31, 0.0, 1.0)];
+		assert_equiripple(&design(15, &bands), &bands);
+	}
+
+	#[test]
+	fn test_weights() {
+		let bands = low_pass_bands(0.1, 0.15, 10.0);
+		let design = design::<f64>(41, &bands);
+		assert_equiripple(&design, &bands);
+		let ratio = design.band_deviations[0] / design.band_deviations[1];
+		assert!((ratio - 10.0).abs() < 0.1);
+		assert!(design.within_tolerances(&[design.band_deviations[0], design.band_deviations[1]]));
+		assert!(!design.within_tolerances(&[design.band_deviations[0], design.band_deviations[1] / 2.0]));
+	}
+
+	#[test]
+	fn test_band_pass() {
+		let bands = vec![
+			Band::new(0.0, 0.1, 0.0, 1.0),
+			Band::new(0.15, 0.3, 1.0, 1.0),
+			Band::new(0.35, 0.5, 0.0, 1.0),
+		];
+		assert_equiripple(&design(45, &bands), &bands);
+	}
+
+	#[test]
+	fn test_estimate_length() {
+		assert_eq!(estimate_length(0.01, 0.01, 0.1), 20);
+		let length = estimate_length(0.01, 0.001, 0.05);
+		let design = design::<f64>(length, &low_pass_bands(0.1, 0.15, 10.0));
+		assert!(design.band_deviations[0] < 0.01 * 1.2);
+		assert!(design.band_deviations[1] < 0.001 * 1.2);
+		assert!(stopband_attenuation_decibels(design.band_deviations[1]) > 58.0);
+		assert!((passband_ripple_decibels(0.01) - 0.1737).abs() < 1e-4);
+	}
+
+	#[test]
+	fn test_convolution() {
+		use crate::convolution;
+		let kernel = design::<f64>(31, &low_pass_bands(0.1, 0.2, 1.0)).kernel;
+		let signal: Vec<_> = (0..200).map(|x| (x as f64 * 2.0 * consts::PI * 0.4).cos()).collect();
+		let output = convolution::convolve_signal(&signal, &kernel);
+		output[31..200].iter().for_each(|sample| assert!(sample.abs() < 0.01));
+	}
+}
@@ -1,47 +1,47 @@
 use crate::bin::Bin;
+use crate::float::Float;
 use crate::fourier_transform::FourierTransform;
 use crate::rectangular::Rectangular;
 use crate::window::Window;
 use rayon::prelude::*;
 use std::collections::VecDeque;
-use std::iter;
-use std::iter::FromIterator;
-use std::marker::PhantomData;
 use super::Sample;
 
-pub struct ShortTimeAnalyser<'a, T> {
-	signal: &'a [Sample],
-	window: &'a Window,
+pub struct ShortTimeAnalyser<'a, T, S = Sample> {
+	signal: &'a [S],
+	window: &'a Window<S>,
+	transform: &'a T,
 
 	frame_spacing: usize,
 	loop_frame_count: usize,
-	_transform: PhantomData<T>,
 }
 
-impl<'a, T> ShortTimeAnalyser<'a, T> where T: FourierTransform {
-	pub fn new(signal: &'a [Sample], overlap: usize, window: &'a Window) -> Self {
+impl<'a, T, S> ShortTimeAnalyser<'a, T, S> where T: FourierTransform<S>, S: Float {
+	/// The transform must be planned for the width of the window
+	pub fn new(signal: &'a [S], overlap: usize, window: &'a Window<S>, transform: &'a T) -> Self {
 		assert!(overlap < window.width());
+		assert_eq!(transform.signal_length(), window.width());
 		let frame_spacing = window.width() - overlap;
 		ShortTimeAnalyser {
 			signal,
 			window,
+			transform,
 			frame_spacing,
 			loop_frame_count: (signal.len() - window.width()) / frame_spacing,
-			_transform: Default::default(),
 		}
 	}
 
-	pub fn calculate_frame(&self, frame_index: usize) -> Vec<Bin<Rectangular>> {
+	pub fn calculate_frame(&self, frame_index: usize) -> Vec<Bin<Rectangular<S>>> {
 		assert!(frame_index < self.total_frames());
 		let frame_start = frame_index * self.frame_spacing;
 		if frame_index <= self.loop_frame_count {
 			debug_assert!(frame_start + self.window.width() <= self.signal.len());
 			let frame = &self.signal[frame_start..frame_start + self.window.width()];
-			T::analysis(&self.window.apply(frame))
+			self.transform.analysis(&self.window.apply(frame))
 		} else {
 			debug_assert!(frame_start + self.window.width() > self.signal.len());
 			let final_frame = self.window.apply(&self.signal[frame_start..]);
-			T::analysis_extend(&final_frame, self.window.width())
+			self.transform.analysis(&final_frame)
 		}
 	}
 
@@ -49,65 +49,110 @@ impl<'a, T> ShortTimeAnalyser<'a, T> where T: FourierTransform {
 		self.loop_frame_count + 1
 	}
 
-	pub fn calculate_all(&self) -> Vec<Vec<Bin<Rectangular>>> {
+	pub fn calculate_all(&self) -> Vec<Vec<Bin<Rectangular<S>>>> {
 		(0..self.total_frames()).into_par_iter().map(|frame_index| self.calculate_frame(frame_index)).collect()
 	}
 }
 
-pub struct ShortTimeSynthesiser<'a, T> {
-	samples: VecDeque<Sample>,
-	window: &'a Window,
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Normalisation {
+	/// Overlap adds the windowed frames which is only exact for windows
+	/// that are constant overlap add after being applied twice
+	None,
+	/// Divides by the sum of the overlapping squared windows which reconstructs frames
+	/// analysed with the same window for any window that is nonzero overlap add
+	WeightedOverlapAdd,
+}
+
+pub struct ShortTimeSynthesiser<'a, T, S = Sample> {
+	samples: VecDeque<S>,
+	weights: VecDeque<S>,
+	normalisation: Normalisation,
+	window: &'a Window<S>,
+	transform: &'a T,
 	overlap: usize,
 	frame_spacing: usize,
 	frame_complete_length: usize,
 	overlapping_frames_count: usize,
-	_transform: PhantomData<T>,
 }
 
-impl<'a, T> ShortTimeSynthesiser<'a, T> where T: FourierTransform {
-	pub fn new(overlap: usize, window: &'a Window) -> Self {
+impl<'a, T, S> ShortTimeSynthesiser<'a, T, S> where T: FourierTransform<S>, S: Float {
+	/// The transform must be planned for the width of the window
+	pub fn new(overlap: usize, window: &'a Window<S>, transform: &'a T) -> Self {
+		Self::with_normalisation(overlap, window, transform, Normalisation::None)
+	}
+
+	/// The transform must be planned for the width of the window
+	pub fn with_normalisation(overlap: usize, window: &'a Window<S>, transform: &'a T,
+	                          normalisation: Normalisation) -> Self {
 		assert!(overlap < window.width());
+		assert_eq!(transform.signal_length(), window.width());
 		let frame_spacing = window.width() - overlap;
 		ShortTimeSynthesiser {
-			samples: VecDeque::from_iter(iter::repeat(0.0).take(overlap)),
+			samples: VecDeque::from(vec![S::zero(); overlap]),
+			weights: VecDeque::new(),
+			normalisation,
 			window,
+			transform,
 			overlap,
 			frame_spacing,
 			frame_complete_length: window.width() + overlap,
 			overlapping_frames_count: (window.width() as f64 / frame_spacing as f64).ceil() as usize,
-			_transform: Default::default(),
 		}
 	}
 
-	pub fn push_frames(&mut self, frames: &Vec<Vec<Bin<Rectangular>>>) {
+	pub fn push_frames(&mut self, frames: &[Vec<Bin<Rectangular<S>>>]) {
 		let complete_end = self.samples.len() - self.overlap;
-		(0..(self.frame_spacing * frames.len())).for_each(|_| self.samples.push_back(0.0));
-		for level in 0..self.overlapping_frames_count {
-			let parallel_frame_count = (frames.len() + self.overlapping_frames_count)
-				.saturating_sub(level + 1) / self.overlapping_frames_count;
-			(0..parallel_frame_count).into_par_iter().for_each(|index| {
-				let frame_index = level + (index * self.overlapping_frames_count);
-				let frame = T::synthesis(&frames[frame_index], self.window.width());
-				let frame_start = complete_end + (frame_index * self.frame_spacing);
+		(0..(self.frame_spacing * frames.len())).for_each(|_| self.samples.push_back(S::zero()));
+		if self.normalisation == Normalisation::WeightedOverlapAdd {
+			self.weights.resize(self.samples.len(), S::zero());
+			for frame_index in 0..frames.len() {
+				let frame_start = complete_end + frame_index * self.frame_spacing;
+				self.weights.range_mut(frame_start..frame_start + self.window.width())
+					.zip(self.window.samples()).for_each(|(weight, sample)| *weight += *sample * *sample);
+			}
+		}
+
+		let (window, transform) = (self.window, self.transform);
+		let overlapping_frames_count = self.overlapping_frames_count;
+		let level_spacing = self.frame_spacing * overlapping_frames_count;
+		let samples = self.samples.make_contiguous();
+		for level in 0..usize::min(overlapping_frames_count, frames.len()) {
+			let parallel_frame_count = (frames.len() + overlapping_frames_count)
+				.saturating_sub(level + 1) / overlapping_frames_count;
+			let level_start = complete_end + (level * self.frame_spacing);
+
+			// Frames within the same level never overlap so each can be written independently
+			samples[level_start..].par_chunks_mut(level_spacing).take(parallel_frame_count)
+				.enumerate().for_each(|(index, frame_samples)| {
+				let frame_index = level + (index * overlapping_frames_count);
+				let frame = transform.synthesis(&frames[frame_index]);
 				for (index, sample) in frame.iter().enumerate() {
-					let sample_index = frame_start + index;
-					let pointer = &self.samples[sample_index] as *const _ as *mut Sample;
-					unsafe { *pointer += self.window.apply_single(sample, index); }
+					frame_samples[index] += window.apply_single(sample, index);
 				}
 			});
 		}
 	}
 
-	pub fn flush_ready(&mut self) -> Vec<Sample> {
+	pub fn flush_ready(&mut self) -> Vec<S> {
 		let mut samples = Vec::new();
 		while self.samples.len() > self.frame_complete_length {
-			samples.push(self.samples.pop_front().unwrap());
+			samples.push(self.pop_sample());
 		}
 		samples
 	}
 
-	pub fn flush_all(self) -> Vec<Sample> {
-		self.samples.into_iter().collect()
+	pub fn flush_all(mut self) -> Vec<S> {
+		(0..self.samples.len()).map(|_| self.pop_sample()).collect()
+	}
+
+	fn pop_sample(&mut self) -> S {
+		let sample = self.samples.pop_front().unwrap();
+		match self.weights.pop_front() {
+			// Samples where every window is close to zero are left as they are
+			Some(weight) if weight > S::convert(1e-10) => sample / weight,
+			_ => sample,
+		}
 	}
 }
 
@@ -124,7 +169,8 @@ mod tests {
 	fn test_analysis() {
 		let signal = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 6.0, 6.0];
 		let window = Window::generate::<window::Hann>(4);
-		let matrix = ShortTimeAnalyser::<CorrelationFourier>::new(&signal, 2, &window).calculate_all();
+		let transform = CorrelationFourier::new(4);
+		let matrix = ShortTimeAnalyser::new(&signal, 2, &window, &transform).calculate_all();
 		let matrix: Vec<Vec<_>> = matrix.into_iter().map(|frame| frame.into_iter().map(|bin| {
 			Bin(Rectangular {
 				cosine: math::approximate(bin.cosine),
@@ -154,20 +200,47 @@ mod tests {
 	fn test_synthesis() {
 		let signal: Vec<_> = (0..200).map(|x| x as f64).collect();
 		let analysis_window = Window::generate::<window::Hann>(100);
-		let matrix = ShortTimeAnalyser::<CorrelationFourier>::new(&signal, 50, &analysis_window).calculate_all();
+		let transform = CorrelationFourier::new(100);
+		let matrix = ShortTimeAnalyser::new(&signal, 50, &analysis_window, &transform).calculate_all();
 
 		let synthesis_window = Window::generate::<window::Dirichlet>(100);
-		let mut synthesiser = ShortTimeSynthesiser::<CorrelationFourier>::new(50, &synthesis_window);
+		let mut synthesiser = ShortTimeSynthesiser::new(50, &synthesis_window, &transform);
 		synthesiser.push_frames(&matrix);
 		let signal: Vec<_> = synthesiser.flush_all().iter().map(|sample| sample.round()).collect();
 		assert_eq!(utility::find_peak(&signal), Some(&151.0));
 	}
 
+	#[test]
+	fn test_weighted_overlap_add() {
+		let signal: Vec<_> = (0..300).map(|x| ((x * 7) % 11) as f64 - 5.0 + (x as f64 * 0.1).sin()).collect();
+		let transform = CorrelationFourier::new(64);
+		for (window, overlap) in [(Window::generate::<window::Hann>(64), 40),
+		                          (Window::generate_periodic::<window::Hamming>(64), 13),
+		                          (Window::generate_parametric(&window::Kaiser { beta: 4.0 }, 64), 0)].iter() {
+			assert!(window.overlap_sums(*overlap).is_nonzero_overlap_add());
+			let matrix = ShortTimeAnalyser::new(&signal, *overlap, window, &transform).calculate_all();
+			let mut synthesiser = ShortTimeSynthesiser::with_normalisation(*overlap, window, &transform,
+				Normalisation::WeightedOverlapAdd);
+			synthesiser.push_frames(&matrix[..2]);
+			let mut output = synthesiser.flush_ready();
+			synthesiser.push_frames(&matrix[2..]);
+			output.append(&mut synthesiser.flush_ready());
+			output.append(&mut synthesiser.flush_all());
+
+			// The first and last samples of the symmetric windows are zero
+			assert!(output.len() >= 256);
+			for (sample, expected) in output[1..output.len() - 1].iter().zip(signal[1..].iter()) {
+				assert!((sample - expected).abs() < 1e-9, "{} {}", sample, expected);
+			}
+		}
+	}
+
 	#[test]
 	fn test_analysis_total_frames() {
 		let signal: Vec<_> = (0..11025).map(|x| x as f64).collect();
 		let analysis_window = Window::generate::<window::Hann>(128);
-		let analyser = ShortTimeAnalyser::<CorrelationFourier>::new(&signal, 120, &analysis_window);
+		let transform = CorrelationFourier::new(128);
+		let analyser = ShortTimeAnalyser::new(&signal, 120, &analysis_window, &transform);
 		assert_eq!(analyser.total_frames(), analyser.calculate_all().len());
 	}
 
@@ -178,9 +251,10 @@ mod tests {
 		let signal: Vec<_> = (0..8192).map(|x| x as f64).collect();
 		let window = Window::generate::<window::Sine>(256);
 		let overlap = 128;
+		let transform = FastFourier::new(256);
 		bench.iter(|| {
-			let analysis = ShortTimeAnalyser::<FastFourier>::new(&signal, overlap, &window).calculate_all();
-			let mut synthesiser = ShortTimeSynthesiser::<FastFourier>::new(overlap, &window);
+			let analysis = ShortTimeAnalyser::new(&signal, overlap, &window, &transform).calculate_all();
+			let mut synthesiser = ShortTimeSynthesiser::new(overlap, &window, &transform);
 			synthesiser.push_frames(&analysis);
 			synthesiser.flush_all()
 		});
@@ -0,0 +1,162 @@
+use crate::biquad::Coefficients;
+use crate::float::Float;
+use std::f64::consts;
+use super::{Hertz, Sample, SampleRate};
+
+/// `y[n] = a0 x[n] + a1 x[n-1] + b1 y[n-1]` (chapter 19 of The Scientist and Engineer's Guide to DSP)
+#[derive(Debug, Clone)]
+pub struct SinglePole<S = Sample> {
+	a0: S,
+	a1: S,
+	b1: S,
+	previous_input: S,
+	previous_output: S,
+}
+
+impl<S> SinglePole<S> where S: Float {
+	pub fn new(a0: f64, a1: f64, b1: f64) -> Self {
+		assert!(b1.abs() < 1.0);
+		SinglePole {
+			a0: S::convert(a0),
+			a1: S::convert(a1),
+			b1: S::convert(b1),
+			previous_input: S::zero(),
+			previous_output: S::zero(),
+		}
+	}
+
+	/// Low pass filter with a gain of one at zero frequency
+	pub fn low_pass(cutoff: Hertz, sample_rate: SampleRate) -> Self {
+		let decay = decay(cutoff, sample_rate);
+		Self::new(1.0 - decay, 0.0, decay)
+	}
+
+	/// High pass filter with a gain of one at the Nyquist frequency
+	pub fn high_pass(cutoff: Hertz, sample_rate: SampleRate) -> Self {
+		let decay = decay(cutoff, sample_rate);
+		Self::new((1.0 + decay) / 2.0, -(1.0 + decay) / 2.0, decay)
+	}
+
+	/// Low pass filter whose impulse response decays to `1 / e` after a number of samples
+	pub fn from_time_constant(samples: f64) -> Self {
+		assert!(samples > 0.0);
+		let decay = (-1.0 / samples).exp();
+		Self::new(1.0 - decay, 0.0, decay)
+	}
+
+	pub fn coefficients(&self) -> (S, S, S) {
+		(self.a0, self.a1, self.b1)
+	}
+
+	pub fn reset(&mut self) {
+		self.previous_input = S::zero();
+		self.previous_output = S::zero();
+	}
+
+	pub fn process_sample(&mut self, sample: S) -> S {
+		let output = self.a0 * sample + self.a1 * self.previous_input + self.b1 * self.previous_output;
+		self.previous_input = sample;
+		self.previous_output = output;
+		output
+	}
+
+	pub fn process(&mut self, signal: &[S]) -> Vec<S> {
+		signal.iter().map(|sample| self.process_sample(*sample)).collect()
+	}
+
+	pub fn process_in_place(&mut self, signal: &mut [S]) {
+		signal.iter_mut().for_each(|sample| *sample = self.process_sample(*sample));
+	}
+}
+
+/// Amount of the previous output kept by each sample for a cutoff
+pub fn decay(cutoff: Hertz, sample_rate: SampleRate) -> f64 {
+	assert!(cutoff > 0.0 && cutoff < sample_rate as f64 / 2.0);
+	(-2.0 * consts::PI * cutoff / sample_rate as f64).exp()
+}
+
+/// Pole radius and gain shared by the narrow band designs
+fn narrow_band(center: Hertz, bandwidth: Hertz, sample_rate: SampleRate) -> (f64, f64, f64) {
+	assert!(center > 0.0 && center < sample_rate as f64 / 2.0);
+	let radius = 1.0 - 3.0 * bandwidth / sample_rate as f64;
+	assert!(radius > 0.0 && radius < 1.0);
+	let cosine = (2.0 * consts::PI * center / sample_rate as f64).cos();
+	let gain = (1.0 - 2.0 * radius * cosine + radius * radius) / (2.0 - 2.0 * cosine);
+	(radius, cosine, gain)
+}
+
+/// Band pass filter with a bandwidth measured at the half power points
+pub fn narrow_band_pass<S>(center: Hertz, bandwidth: Hertz, sample_rate: SampleRate) -> Coefficients<S> where S: Float {
+	let (radius, cosine, gain) = narrow_band(center, bandwidth, sample_rate);
+	Coefficients::new(1.0 - gain, 2.0 * (gain - radius) * cosine, radius * radius - gain,
+		1.0, -2.0 * radius * cosine, radius * radius)
+}
+
+/// Band reject filter with a gain of one at zero frequency
+pub fn notch<S>(center: Hertz, bandwidth: Hertz, sample_rate: SampleRate) -> Coefficients<S> where S: Float {
+	let (radius, cosine, gain) = narrow_band(center, bandwidth, sample_rate);
+	Coefficients::new(gain, -2.0 * gain * cosine, gain,
+		1.0, -2.0 * radius * cosine, radius * radius)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::biquad::{Biquad, Form};
+	use crate::frequency_response;
+	use super::*;
+
+	const SAMPLE_RATE: SampleRate = 8000;
+
+	fn gain(filter: &SinglePole, frequency: Hertz) -> f64 {
+		let mut filter = filter.clone();
+		filter.reset();
+		let mut impulse = vec![0.0; 4096];
+		impulse[0] = 1.0;
+		let response = filter.process(&impulse);
+		frequency_response::kernel_response(&response, SAMPLE_RATE, &[frequency]).magnitudes()[0]
+	}
+
+	#[test]
+	fn test_low_pass() {
+		let mut filter = SinglePole::low_pass(100.0, SAMPLE_RATE);
+		let decay = (-2.0 * consts::PI * 100.0 / 8000.0).exp();
+		let response = filter.process(&[1.0, 0.0, 0.0, 0.0]);
+		for (index, sample) in response.iter().enumerate() {
+			assert!((sample - (1.0 - decay) * decay.powi(index as i32)).abs() < 1e-12);
+		}
+
+		assert!((gain(&filter, 0.0) - 1.0).abs() < 1e-9);
+		assert!(gain(&filter, 1000.0) < 0.2);
+		let filter = SinglePole::<f64>::from_time_constant(10.0);
+		assert!((filter.coefficients().2 - (-0.1_f64).exp()).abs() < 1e-15);
+	}
+
+	#[test]
+	fn test_high_pass() {
+		let mut filter = SinglePole::high_pass(500.0, SAMPLE_RATE);
+		assert!(gain(&filter, 0.0) < 1e-9);
+		assert!((gain(&filter, 4000.0) - 1.0).abs() < 1e-9);
+
+		let mut step = vec![1.0; 200];
+		filter.process_in_place(&mut step);
+		assert!((step[0] - (1.0 + decay(500.0, SAMPLE_RATE)) / 2.0).abs() < 1e-12);
+		assert!(step[199].abs() < 1e-9);
+	}
+
+	#[test]
+	fn test_narrow_band() {
+		let band_pass = narrow_band_pass::<f64>(1000.0, 40.0, SAMPLE_RATE);
+		assert!((band_pass.gain(SAMPLE_RATE, 1000.0) - 1.0).abs() < 2e-2);
+		assert!(band_pass.gain(SAMPLE_RATE, 500.0) < 0.1);
+		assert!((band_pass.gain(SAMPLE_RATE, 1020.0) - consts::FRAC_1_SQRT_2).abs() < 5e-2);
+
+		let notch = notch::<f64>(1000.0, 40.0, SAMPLE_RATE);
+		assert!((notch.gain(SAMPLE_RATE, 0.0) - 1.0).abs() < 1e-12);
+		assert!(notch.gain(SAMPLE_RATE, 1000.0) < 1e-9);
+		assert!((notch.gain(SAMPLE_RATE, 3000.0) - 1.0).abs() < 2e-2);
+
+		let signal: Vec<_> = (0..4000).map(|x| (2.0 * consts::PI * 1000.0 * x as f64 / 8000.0).sin()).collect();
+		let output = Biquad::new(notch, Form::DirectOne).process(&signal);
+		output[3000..].iter().for_each(|sample| assert!(sample.abs() < 1e-3));
+	}
+}
@@ -45,6 +45,6 @@ mod tests {
 	#[test]
 	fn test_standard_deviation() {
 		assert!(standard_deviation(&[0.0]).is_nan());
-		assert_eq!(standard_deviation(&[-1.0, 0.0, 1.0, 2.0]), (5.0 as f64 / 3.0).sqrt());
+		assert_eq!(standard_deviation(&[-1.0, 0.0, 1.0, 2.0]), (5.0_f64 / 3.0).sqrt());
 	}
 }
\ No newline at end of file
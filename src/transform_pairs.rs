@@ -1,10 +1,10 @@
-use std::f64::consts;
+use crate::float::Float;
 use std::iter;
-use super::Sample;
 
 /// Computes the inverse discrete fourier transform of a rectangular pulse in the frequency domain
-pub fn rectangular_pulse(cutoff_frequency: f64, signal_length: usize) -> Vec<Sample> {
-	iter::once(1.0).chain((1..signal_length)
-		.map(|i| (2.0 * cutoff_frequency * i as f64 * consts::PI).sin() / (i as f64 * consts::PI))
+pub fn rectangular_pulse<S>(cutoff_frequency: S, signal_length: usize) -> Vec<S> where S: Float {
+	let two = S::convert(2.0);
+	iter::once(S::one()).chain((1..signal_length)
+		.map(|i| (two * cutoff_frequency * S::from_index(i) * S::PI()).sin() / (S::from_index(i) * S::PI()))
 	).collect()
 }
\ No newline at end of file
@@ -30,14 +30,14 @@ macro_rules! wrapper {
 
 macro_rules! cross_cast {
 	($type: ident, $a: ident, $b: ident) => {
-		impl From<$type<$b>> for $type<$a> {
-			fn from(other: $type<$b>) -> Self {
+		impl<S> From<$type<$b<S>>> for $type<$a<S>> where $a<S>: From<$b<S>> {
+			fn from(other: $type<$b<S>>) -> Self {
 				$type(other.take().into())
 			}
 		}
 
-		impl From<$type<$a>> for $type<$b> {
-			fn from(other: $type<$a>) -> Self {
+		impl<S> From<$type<$a<S>>> for $type<$b<S>> where $b<S>: From<$a<S>> {
+			fn from(other: $type<$a<S>>) -> Self {
 				$type(other.take().into())
 			}
 		}
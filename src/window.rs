@@ -1,71 +1,433 @@
+use crate::float::Float;
+use crate::math;
 use std::f64::consts;
 use super::Sample;
 
+/// Symmetric windows are used for filter design and periodic windows for spectral analysis
+/// A periodic window is a symmetric window one sample longer with the last sample removed,
+/// which makes windows such as Hann sum to a constant when overlapped
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Symmetry {
+	Symmetric,
+	Periodic,
+}
+
 #[derive(Debug, Clone)]
-pub struct Window {
-	window: Vec<Sample>,
+pub struct Window<S = Sample> {
+	window: Vec<S>,
 }
 
-impl Window {
-	pub fn generate<F>(length: usize) -> Window where F: WindowFunction {
+impl<S> Window<S> where S: Float {
+	pub fn generate<F>(length: usize) -> Window<S> where F: WindowFunction {
+		Self::generate_symmetry(&F::default(), length, Symmetry::Symmetric)
+	}
+
+	pub fn generate_periodic<F>(length: usize) -> Window<S> where F: WindowFunction {
+		Self::generate_symmetry(&F::default(), length, Symmetry::Periodic)
+	}
+
+	/// Generates a symmetric window from a function that carries parameters
+	/// Every `WindowFunction` is also a `ParametricWindow`
+	pub fn generate_parametric<P>(function: &P, length: usize) -> Window<S> where P: ParametricWindow {
+		Self::generate_symmetry(function, length, Symmetry::Symmetric)
+	}
+
+	pub fn generate_symmetry<P>(function: &P, length: usize, symmetry: Symmetry) -> Window<S> where P: ParametricWindow {
 		assert!(length > 0);
-		Window {
-			window: F::generate(length),
-		}
+		let window = match symmetry {
+			Symmetry::Symmetric => function.samples(length),
+			Symmetry::Periodic => function.periodic_samples(length),
+		};
+		Window { window }
 	}
 
-	pub fn apply(&self, signal: &[Sample]) -> Vec<Sample> {
+	/// Kaiser-Bessel derived window for lapped transforms with an even length
+	/// Larger alpha values trade a wider main lobe for lower sidelobes
+	pub fn kaiser_bessel_derived(length: usize, alpha: f64) -> Window<S> {
+		assert!(length > 0 && length.is_multiple_of(2));
+		let half_length = length / 2;
+		let kaiser: Vec<f64> = (0..=half_length).map(|n| {
+			let position = 2.0 * n as f64 / half_length as f64 - 1.0;
+			math::bessel_zero(std::f64::consts::PI * alpha * (1.0 - position * position).sqrt())
+		}).collect();
+
+		let total: f64 = kaiser.iter().sum();
+		let mut window: Vec<S> = kaiser.iter().take(half_length)
+			.scan(0.0, |cumulative, sample| {
+				*cumulative += sample;
+				Some(S::convert((*cumulative / total).sqrt()))
+			}).collect();
+		window.extend(window.clone().into_iter().rev());
+		Window { window }
+	}
+
+	/// Checks that the window is symmetric and that the squares of samples
+	/// half a window apart sum to one, allowing time domain aliasing cancellation
+	pub fn is_princen_bradley(&self) -> bool {
+		let width = self.width();
+		let half_width = width / 2;
+		let tolerance = S::convert(1e-6);
+		width.is_multiple_of(2) && (0..half_width).all(|n| {
+			let (sample, opposite) = (self.window[n], self.window[n + half_width]);
+			(self.window[width - 1 - n] - sample).abs() < tolerance &&
+				(sample * sample + opposite * opposite - S::one()).abs() < tolerance
+		})
+	}
+
+	/// Sums the window and its square with copies of itself shifted by multiples of the frame spacing
+	pub fn overlap_sums(&self, overlap: usize) -> OverlapSums<S> {
+		assert!(overlap < self.width());
+		let frame_spacing = self.width() - overlap;
+		let sum = |map: &dyn Fn(S) -> S| (0..frame_spacing).map(|offset| self.window[offset..]
+			.iter().step_by(frame_spacing).fold(S::zero(), |sum, sample| sum + map(*sample))).collect();
+		OverlapSums { window: sum(&|sample| sample), square: sum(&|sample| sample * sample) }
+	}
+
+	pub fn apply(&self, signal: &[S]) -> Vec<S> {
 		signal.iter().enumerate()
 		      .map(|(index, sample)| self.apply_single(sample, index))
 		      .collect()
 	}
 
-	pub fn apply_single(&self, sample: &Sample, index: usize) -> Sample {
-		self.window.get(index).unwrap_or(&0.0) * sample
+	pub fn apply_single(&self, sample: &S, index: usize) -> S {
+		*self.window.get(index).unwrap_or(&S::zero()) * *sample
 	}
 
-	pub fn normalize_amplitude(&self, overlap_factor: f64) -> Window {
+	pub fn normalize_amplitude(&self, overlap_factor: f64) -> Window<S> {
 		assert!(overlap_factor > 0.0);
+		let overlap_factor = S::convert(overlap_factor);
 		Window {
-			window: self.window.iter().map(|x| (2.0 * x) / overlap_factor).collect()
+			window: self.window.iter().map(|x| (S::convert(2.0) * *x) / overlap_factor).collect()
 		}
 	}
 
 	pub fn width(&self) -> usize {
 		self.window.len()
 	}
+
+	pub fn samples(&self) -> &[S] {
+		&self.window
+	}
+}
+
+/// Sums of overlapping windows at each sample within a frame spacing once every frame overlaps
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlapSums<S = Sample> {
+	pub window: Vec<S>,
+	pub square: Vec<S>,
+}
+
+impl<S> OverlapSums<S> where S: Float {
+	/// Checks for constant overlap add where unmodified frames reconstruct the signal scaled by the gain
+	pub fn is_constant_overlap_add(&self) -> bool {
+		let gain = self.gain();
+		let tolerance = S::convert(1e-6) * gain.abs();
+		self.window.iter().all(|sum| (*sum - gain).abs() < tolerance)
+	}
+
+	/// Checks for nonzero overlap add where the squared windows never sum to zero
+	/// which allows weighted overlap add to invert the analysis
+	pub fn is_nonzero_overlap_add(&self) -> bool {
+		self.square.iter().all(|sum| *sum > S::convert(1e-10))
+	}
+
+	/// Average gain of overlap adding the window which is exact for constant overlap add
+	pub fn gain(&self) -> S {
+		self.window.iter().fold(S::zero(), |sum, sample| sum + *sample) / S::from_index(self.window.len())
+	}
+
+	/// Average gain of overlap adding the window after it is applied a second time for synthesis
+	pub fn square_gain(&self) -> S {
+		self.square.iter().fold(S::zero(), |sum, sample| sum + *sample) / S::from_index(self.square.len())
+	}
+}
+
+/// Window functions generate symmetric windows where a length of one is a single sample of one
+pub trait WindowFunction: Default {
+	fn generate<S>(length: usize) -> Vec<S> where S: Float;
+}
+
+/// Window function with parameters that control its shape
+pub trait ParametricWindow {
+	fn samples<S>(&self, length: usize) -> Vec<S> where S: Float;
+
+	/// Samples of the symmetric window one sample longer with the last sample removed
+	fn periodic_samples<S>(&self, length: usize) -> Vec<S> where S: Float {
+		if length == 1 {
+			return vec![S::one()];
+		}
+
+		let mut window = self.samples(length + 1);
+		window.truncate(length);
+		window
+	}
+}
+
+impl<F> ParametricWindow for F where F: WindowFunction {
+	fn samples<S>(&self, length: usize) -> Vec<S> where S: Float {
+		F::generate(length)
+	}
 }
 
-pub trait WindowFunction {
-	fn generate(length: usize) -> Vec<Sample>;
+/// Position of each sample as a fraction of the distance between the first and last samples
+/// A single sample is at the centre of the window
+fn positions(length: usize) -> impl Iterator<Item=f64> {
+	let denominator = (length - 1) as f64;
+	(0..length).map(move |n| if length == 1 { 0.5 } else { n as f64 / denominator })
 }
 
+/// Sum of cosines with alternating signs `Σ (-1)^k a_k cos(2πkn / (N - 1))`
+fn cosine_sum<S>(coefficients: &[f64], length: usize) -> Vec<S> where S: Float {
+	positions(length).map(|position| {
+		S::convert(coefficients.iter().enumerate().map(|(order, coefficient)| {
+			let sign = if order.is_multiple_of(2) { 1.0 } else { -1.0 };
+			sign * coefficient * (consts::TAU * order as f64 * position).cos()
+		}).sum())
+	}).collect()
+}
+
+#[derive(Debug, Default, Copy, Clone)]
 pub struct Sine();
 
 impl WindowFunction for Sine {
-	fn generate(length: usize) -> Vec<Sample> {
-		let denominator = (length - 1) as f64;
+	fn generate<S>(length: usize) -> Vec<S> where S: Float {
+		if length == 1 {
+			return vec![S::one()];
+		}
+
+		let denominator = S::from_index(length - 1);
+		(0..length)
+			.map(|n| ((S::PI() * S::from_index(n)) / denominator).sin())
+			.collect()
+	}
+}
+
+/// Sine window offset by half a sample for lapped transforms
+#[derive(Debug, Default, Copy, Clone)]
+pub struct ShiftedSine();
+
+impl WindowFunction for ShiftedSine {
+	fn generate<S>(length: usize) -> Vec<S> where S: Float {
+		let length_float = S::from_index(length);
 		(0..length)
-			.map(|n| ((consts::PI * n as f64) / denominator).sin())
+			.map(|n| ((S::PI() * (S::from_index(n) + S::convert(0.5))) / length_float).sin())
 			.collect()
 	}
 }
 
+#[derive(Debug, Default, Copy, Clone)]
 pub struct Hann();
 
 impl WindowFunction for Hann {
-	fn generate(length: usize) -> Vec<Sample> {
-		let mut window = Sine::generate(length);
+	fn generate<S>(length: usize) -> Vec<S> where S: Float {
+		let mut window: Vec<S> = Sine::generate(length);
 		window.iter_mut().for_each(|x| *x = *x * *x);
 		window
 	}
 }
 
+#[derive(Debug, Default, Copy, Clone)]
 pub struct Dirichlet();
 
 impl WindowFunction for Dirichlet {
-	fn generate(length: usize) -> Vec<Sample> {
-		vec![1.0; length]
+	fn generate<S>(length: usize) -> Vec<S> where S: Float {
+		vec![S::one(); length]
+	}
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Hamming();
+
+impl WindowFunction for Hamming {
+	fn generate<S>(length: usize) -> Vec<S> where S: Float {
+		cosine_sum(&[0.54, 0.46], length)
+	}
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Blackman();
+
+impl WindowFunction for Blackman {
+	fn generate<S>(length: usize) -> Vec<S> where S: Float {
+		cosine_sum(&[0.42, 0.5, 0.08], length)
+	}
+}
+
+/// Four term Blackman-Harris window with sidelobes below -92 dB
+#[derive(Debug, Default, Copy, Clone)]
+pub struct BlackmanHarris();
+
+impl WindowFunction for BlackmanHarris {
+	fn generate<S>(length: usize) -> Vec<S> where S: Float {
+		cosine_sum(&[0.35875, 0.48829, 0.14128, 0.01168], length)
+	}
+}
+
+/// Four term Nuttall window with a continuous first derivative
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Nuttall();
+
+impl WindowFunction for Nuttall {
+	fn generate<S>(length: usize) -> Vec<S> where S: Float {
+		cosine_sum(&[0.355768, 0.487396, 0.144232, 0.012604], length)
+	}
+}
+
+/// Window with a flat passband for measuring the amplitude of sinusoids
+#[derive(Debug, Default, Copy, Clone)]
+pub struct FlatTop();
+
+impl WindowFunction for FlatTop {
+	fn generate<S>(length: usize) -> Vec<S> where S: Float {
+		cosine_sum(&[0.21557895, 0.41663158, 0.277263158, 0.083578947, 0.006947368], length)
+	}
+}
+
+/// Triangular window that is zero at both ends
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Bartlett();
+
+impl WindowFunction for Bartlett {
+	fn generate<S>(length: usize) -> Vec<S> where S: Float {
+		positions(length).map(|position| S::convert(1.0 - (2.0 * position - 1.0).abs())).collect()
+	}
+}
+
+/// Triangular window that is not zero at either end
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Triangular();
+
+impl WindowFunction for Triangular {
+	fn generate<S>(length: usize) -> Vec<S> where S: Float {
+		let half_width = (length + length % 2) as f64 / 2.0;
+		let center = (length - 1) as f64 / 2.0;
+		(0..length).map(|n| S::convert(1.0 - (n as f64 - center).abs() / half_width)).collect()
+	}
+}
+
+/// Flat window with cosine tapers covering a fraction `alpha` of the window
+/// An alpha of zero is rectangular and an alpha of one is a Hann window
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Tukey {
+	pub alpha: f64,
+}
+
+impl ParametricWindow for Tukey {
+	fn samples<S>(&self, length: usize) -> Vec<S> where S: Float {
+		assert!((0.0..=1.0).contains(&self.alpha));
+		positions(length).map(|position| {
+			let edge = f64::min(position, 1.0 - position);
+			S::convert(if edge >= self.alpha / 2.0 { 1.0 } else {
+				0.5 * (1.0 - (consts::TAU * edge / self.alpha).cos())
+			})
+		}).collect()
+	}
+}
+
+/// Gaussian window with a standard deviation measured in samples
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Gaussian {
+	pub standard_deviation: f64,
+}
+
+impl ParametricWindow for Gaussian {
+	fn samples<S>(&self, length: usize) -> Vec<S> where S: Float {
+		assert!(self.standard_deviation > 0.0);
+		let center = (length - 1) as f64 / 2.0;
+		(0..length).map(|n| {
+			let offset = (n as f64 - center) / self.standard_deviation;
+			S::convert((-0.5 * offset * offset).exp())
+		}).collect()
+	}
+}
+
+/// Kaiser window where larger beta values trade a wider main lobe for lower sidelobes
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Kaiser {
+	pub beta: f64,
+}
+
+impl ParametricWindow for Kaiser {
+	fn samples<S>(&self, length: usize) -> Vec<S> where S: Float {
+		assert!(self.beta >= 0.0);
+		let scale = math::bessel_zero(self.beta);
+		positions(length).map(|position| {
+			let position = 2.0 * position - 1.0;
+			S::convert(math::bessel_zero(self.beta * (1.0 - position * position).max(0.0).sqrt()) / scale)
+		}).collect()
+	}
+}
+
+/// Dolph-Chebyshev window with every sidelobe at the attenuation in decibels
+/// below the main lobe, which is the narrowest main lobe for that attenuation
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DolphChebyshev {
+	pub attenuation: f64,
+}
+
+impl ParametricWindow for DolphChebyshev {
+	fn samples<S>(&self, length: usize) -> Vec<S> where S: Float {
+		assert!(self.attenuation > 0.0);
+		if length == 1 {
+			return vec![S::one()];
+		}
+
+		// Samples the Chebyshev polynomial in the frequency domain and transforms it
+		let order = (length - 1) as f64;
+		let ratio = 10.0_f64.powf(self.attenuation / 20.0);
+		let beta = (ratio.acosh() / order).cosh();
+		let odd = !length.is_multiple_of(2);
+		let spectrum: Vec<(f64, f64)> = (0..length).map(|k| {
+			let x = beta * (consts::PI * k as f64 / length as f64).cos();
+			let value = if x > 1.0 {
+				(order * x.acosh()).cosh()
+			} else if x < -1.0 {
+				let sign = if odd { 1.0 } else { -1.0 };
+				sign * (order * (-x).acosh()).cosh()
+			} else {
+				(order * x.acos()).cos()
+			};
+
+			// Even lengths are shifted by half a sample so that the window is symmetric
+			let shift = if odd { 0.0 } else { consts::PI * k as f64 / length as f64 };
+			(value * shift.cos(), value * shift.sin())
+		}).collect();
+
+		let transform = |index: usize| spectrum.iter().enumerate().map(|(k, (real, imaginary))| {
+			let angle = -consts::TAU * (k * index) as f64 / length as f64;
+			real * angle.cos() - imaginary * angle.sin()
+		}).sum::<f64>();
+
+		let half: Vec<f64> = (0..length.div_ceil(2) + if odd { 0 } else { 1 }).map(transform).collect();
+		let mut window: Vec<f64> = if odd {
+			half.iter().skip(1).rev().chain(half.iter()).cloned().collect()
+		} else {
+			half.iter().skip(1).rev().chain(half.iter().skip(1)).cloned().collect()
+		};
+
+		let maximum = window.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+		window.iter_mut().for_each(|sample| *sample /= maximum);
+		window.into_iter().map(S::convert).collect()
+	}
+}
+
+/// Kaiser-Bessel derived window for lapped transforms with an even length
+/// The window already overlaps with a hop of half its length so it is periodic by definition
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct KaiserBesselDerived {
+	pub alpha: f64,
+}
+
+impl ParametricWindow for KaiserBesselDerived {
+	fn samples<S>(&self, length: usize) -> Vec<S> where S: Float {
+		match length {
+			1 => vec![S::one()],
+			_ => Window::kaiser_bessel_derived(length, self.alpha).window,
+		}
+	}
+
+	fn periodic_samples<S>(&self, length: usize) -> Vec<S> where S: Float {
+		self.samples(length)
 	}
 }
 
@@ -97,13 +459,184 @@ mod tests {
 	#[test]
 	fn test_hann_window_overlap() {
 		let window = Window::generate::<Hann>(512);
-		let mut signal = [0.0; 768];
+		let mut signal = [0.0_f64; 768];
 		for (index, sample) in window.apply(&[1.0; 512]).into_iter().enumerate() {
 			signal[index] += sample;
 			signal[index + 256] += sample;
 		}
 
 		signal.iter_mut().for_each(|x| *x = x.round());
-		assert_eq!(&signal[256..512], &[1.0 as f64; 256][..]);
+		assert_eq!(&signal[256..512], &[1.0_f64; 256][..]);
+	}
+
+	#[test]
+	fn test_periodic_hann_overlap() {
+		for length in [4, 64, 512].iter().cloned() {
+			let window = Window::generate_periodic::<Hann>(length);
+			let hop = length / 2;
+			let mut signal = vec![0.0_f64; hop * 9 + length];
+			for frame in 0..10 {
+				for (index, sample) in window.apply(&vec![1.0; length]).into_iter().enumerate() {
+					signal[frame * hop + index] += sample;
+				}
+			}
+
+			signal[length..hop * 10].iter().for_each(|sample| assert!((sample - 1.0).abs() < 1e-12));
+		}
+
+		let window = Window::generate_periodic::<Hann>(4);
+		let output: Vec<_> = window.apply(&[1.0; 4]).into_iter().map(math::approximate).collect();
+		assert_eq!(output, vec![0.0, 0.5, 1.0, 0.5]);
 	}
-}
\ No newline at end of file
+
+	#[test]
+	fn test_periodic_parametric() {
+		let periodic = Window::generate_symmetry(&Kaiser { beta: 6.0 }, 8, Symmetry::Periodic);
+		let symmetric = Window::generate_parametric(&Kaiser { beta: 6.0 }, 9);
+		assert_eq!(periodic.apply(&[1.0; 8]), symmetric.apply(&[1.0; 8]));
+
+		// Lapped transform windows keep their overlap property when generated as periodic
+		let function = KaiserBesselDerived { alpha: 4.0 };
+		let periodic: Window = Window::generate_symmetry(&function, 64, Symmetry::Periodic);
+		assert_eq!(periodic.width(), 64);
+		assert!(periodic.is_princen_bradley());
+		assert_eq!(periodic.samples(), Window::generate_parametric(&function, 64).samples());
+
+		let window = Window::generate_periodic::<Hamming>(6);
+		let mut sum = [0.0_f64; 12];
+		for frame in 0..3 {
+			window.apply(&[1.0; 6]).iter().enumerate().for_each(|(index, sample)| sum[frame * 3 + index] += sample);
+		}
+		sum[3..9].iter().for_each(|sample| assert!((sample - 1.08).abs() < 1e-12));
+	}
+
+	#[test]
+	fn test_single_sample() {
+		fn assert_single<P>(function: &P) where P: ParametricWindow {
+			for symmetry in [Symmetry::Symmetric, Symmetry::Periodic].iter().cloned() {
+				let window = Window::generate_symmetry(function, 1, symmetry);
+				let sample: f64 = window.apply(&[1.0])[0];
+				assert!((sample - 1.0).abs() < 1e-8, "{:?} {}", symmetry, sample);
+			}
+			let window: Vec<f64> = function.samples(1);
+			assert!((window[0] - 1.0).abs() < 1e-8);
+		}
+
+		assert_single(&Sine());
+		assert_single(&ShiftedSine());
+		assert_single(&Hann());
+		assert_single(&Dirichlet());
+		assert_single(&Hamming());
+		assert_single(&Blackman());
+		assert_single(&BlackmanHarris());
+		assert_single(&Nuttall());
+		assert_single(&FlatTop());
+		assert_single(&Bartlett());
+		assert_single(&Triangular());
+		assert_single(&Tukey { alpha: 0.5 });
+		assert_single(&Gaussian { standard_deviation: 2.0 });
+		assert_single(&Kaiser { beta: 8.0 });
+		assert_single(&DolphChebyshev { attenuation: 50.0 });
+		assert_single(&KaiserBesselDerived { alpha: 4.0 });
+	}
+
+	#[test]
+	fn test_princen_bradley() {
+		assert!(Window::<f64>::generate::<ShiftedSine>(64).is_princen_bradley());
+		assert!(Window::<f64>::kaiser_bessel_derived(64, 4.0).is_princen_bradley());
+		assert!(Window::<f32>::kaiser_bessel_derived(10, 0.5).is_princen_bradley());
+		assert!(!Window::<f64>::generate::<Sine>(64).is_princen_bradley());
+		assert!(!Window::<f64>::generate::<ShiftedSine>(63).is_princen_bradley());
+	}
+
+	#[test]
+	fn test_overlap_sums() {
+		let window: Window = Window::generate_periodic::<Hann>(64);
+		let sums = window.overlap_sums(32);
+		assert!(sums.is_constant_overlap_add() && sums.is_nonzero_overlap_add());
+		assert!((sums.gain() - 1.0).abs() < 1e-12);
+		assert!((sums.square_gain() - 0.75).abs() < 1e-12);
+		assert!((window.overlap_sums(48).gain() - 2.0).abs() < 1e-12);
+		assert!(window.overlap_sums(48).is_constant_overlap_add());
+		assert!(!window.overlap_sums(0).is_nonzero_overlap_add());
+
+		let symmetric: Window = Window::generate::<Hann>(64);
+		let sums = symmetric.overlap_sums(32);
+		assert!(!sums.is_constant_overlap_add() && sums.is_nonzero_overlap_add());
+
+		let hamming: Window = Window::generate_periodic::<Hamming>(6);
+		assert_eq!(hamming.overlap_sums(3).window.len(), 3);
+		assert!((hamming.overlap_sums(3).gain() - 1.08).abs() < 1e-12);
+		assert!(Window::<f64>::generate::<Dirichlet>(64).overlap_sums(0).is_constant_overlap_add());
+	}
+
+	#[test]
+	fn test_kaiser_bessel_derived() {
+		let window = Window::<f64>::kaiser_bessel_derived(8, 0.0);
+		let output: Vec<_> = window.apply(&[1.0; 8]).into_iter().map(math::approximate).collect();
+		let expected: Vec<_> = [0.2, 0.4, 0.6, 0.8, 0.8, 0.6, 0.4, 0.2].iter()
+			.map(|x: &f64| math::approximate(x.sqrt())).collect();
+		assert_eq!(output, expected);
+	}
+
+	fn assert_window<P>(function: &P, expected: &[f64]) where P: ParametricWindow {
+		let window = Window::generate_parametric(function, expected.len());
+		let output = window.apply(&vec![1.0; expected.len()]);
+		for (sample, expected) in output.iter().zip(expected.iter()) {
+			assert!((sample - expected).abs() < 1e-6, "{:?} {:?}", output, expected);
+		}
+	}
+
+	#[test]
+	fn test_cosine_sum_windows() {
+		assert_window(&Hamming(), &[0.08, 0.54, 1.0, 0.54, 0.08]);
+		assert_window(&Blackman(), &[0.0, 0.34, 1.0, 0.34, 0.0]);
+		assert_window(&BlackmanHarris(), &[6e-5, 0.21747, 1.0, 0.21747, 6e-5]);
+		assert_window(&Nuttall(), &[0.0, 0.211536, 1.0, 0.211536, 0.0]);
+		assert_window(&FlatTop(), &[-0.000421051, -0.05473684, 1.000000003, -0.05473684, -0.000421051]);
+		assert_window(&Hann(), &[0.0, 0.5, 1.0, 0.5, 0.0]);
+	}
+
+	#[test]
+	fn test_triangular_windows() {
+		assert_window(&Bartlett(), &[0.0, 0.5, 1.0, 0.5, 0.0]);
+		assert_window(&Triangular(), &[1.0 / 3.0, 2.0 / 3.0, 1.0, 2.0 / 3.0, 1.0 / 3.0]);
+		assert_window(&Triangular(), &[0.25, 0.75, 0.75, 0.25]);
+	}
+
+	#[test]
+	fn test_parametric_windows() {
+		assert_window(&Tukey { alpha: 0.5 }, &[0.0, 1.0, 1.0, 1.0, 0.0]);
+		assert_window(&Tukey { alpha: 0.0 }, &[1.0; 6]);
+		assert_window(&Tukey { alpha: 1.0 }, &Hann::generate::<f64>(9));
+		assert_window(&Gaussian { standard_deviation: 1.0 }, &[(-2.0_f64).exp(), (-0.5_f64).exp(), 1.0, (-0.5_f64).exp(), (-2.0_f64).exp()]);
+		assert_window(&Kaiser { beta: 0.0 }, &[1.0; 7]);
+		let edge = 1.0 / math::bessel_zero(5.0);
+		let middle = math::bessel_zero(5.0 * 0.75_f64.sqrt()) * edge;
+		assert_window(&Kaiser { beta: 5.0 }, &[edge, middle, 1.0, middle, edge]);
+		assert_window(&KaiserBesselDerived { alpha: 0.0 }, &[0.2_f64.sqrt(), 0.4_f64.sqrt(), 0.6_f64.sqrt(),
+			0.8_f64.sqrt(), 0.8_f64.sqrt(), 0.6_f64.sqrt(), 0.4_f64.sqrt(), 0.2_f64.sqrt()]);
+	}
+
+	#[test]
+	fn test_dolph_chebyshev() {
+		for length in [31, 32].iter().cloned() {
+			let window: Vec<f64> = DolphChebyshev { attenuation: 60.0 }.samples(length);
+			assert_eq!(window.len(), length);
+			window.iter().zip(window.iter().rev()).for_each(|(a, b)| assert!((a - b).abs() < 1e-12));
+
+			// Every sidelobe peaks at the attenuation below the main lobe
+			let response: Vec<f64> = (0..=2000).map(|index| {
+				let angle = consts::PI * index as f64 / 2000.0;
+				let (cosine, sine) = window.iter().enumerate().fold((0.0, 0.0), |(cosine, sine), (n, sample)| {
+					(cosine + sample * (angle * n as f64).cos(), sine + sample * (angle * n as f64).sin())
+				});
+				cosine.hypot(sine)
+			}).collect();
+			let first_null = (1..response.len()).find(|index| response[*index] > response[index - 1]).unwrap();
+			let peak = response[first_null..].iter().cloned().fold(0.0, f64::max);
+			let sidelobe = 20.0 * (peak / response[0]).log10();
+			assert!((sidelobe + 60.0).abs() < 0.1, "{}", sidelobe);
+		}
+	}
+}
@@ -0,0 +1,124 @@
+use crate::float::Float;
+use crate::fourier_transform::FourierTransform;
+use crate::window::Window;
+
+/// Spectral properties used to compare windows (Harris 1978)
+/// Widths are in bins and levels are in decibels relative to the peak of the main lobe
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WindowMetrics {
+	/// Gain of a sinusoid at the centre of a bin relative to no window
+	pub coherent_gain: f64,
+	/// Width in bins of a rectangular filter that passes the same noise power
+	pub equivalent_noise_bandwidth: f64,
+	/// Improvement of the signal to noise ratio of a sinusoid which is the inverse of the noise bandwidth
+	pub processing_gain: f64,
+	/// Loss in decibels for a sinusoid half way between two bins
+	pub scalloping_loss: f64,
+	pub highest_sidelobe: f64,
+	/// Width of the main lobe in bins where it is three decibels below its peak
+	pub half_power_width: f64,
+	/// Width of the main lobe in bins where it is six decibels below its peak
+	pub half_amplitude_width: f64,
+	/// Slope of the sidelobe peaks in decibels per octave between one eighth
+	/// and one quarter of the Nyquist frequency
+	pub sidelobe_falloff: f64,
+}
+
+impl<S> Window<S> where S: Float {
+	/// The transform zero pads the window and must be planned for a length
+	/// that is an even multiple of the width of the window
+	pub fn metrics<T>(&self, transform: &T) -> WindowMetrics where T: FourierTransform<S> {
+		let width = self.width();
+		assert!(transform.signal_length().is_multiple_of(2 * width));
+		let padding_factor = transform.signal_length() / width;
+		let samples: Vec<f64> = self.samples().iter().map(|sample| sample.into_f64()).collect();
+		let sum: f64 = samples.iter().sum();
+		let square_sum: f64 = samples.iter().map(|sample| sample * sample).sum();
+		let equivalent_noise_bandwidth = width as f64 * square_sum / (sum * sum);
+
+		let magnitudes: Vec<f64> = transform.analysis(self.samples()).iter()
+			.map(|bin| bin.abs().into_f64()).collect();
+		let decibels: Vec<f64> = magnitudes.iter().map(|magnitude| 20.0 * (magnitude / magnitudes[0]).log10()).collect();
+		let bin = |index: f64| index / padding_factor as f64;
+
+		let main_lobe_end = (1..decibels.len()).find(|index| decibels[*index] > decibels[index - 1])
+			.map(|index| index - 1).unwrap_or(decibels.len() - 1);
+		let peaks: Vec<usize> = (main_lobe_end + 1..decibels.len() - 1)
+			.filter(|index| decibels[*index] >= decibels[index - 1] && decibels[*index] > decibels[index + 1]).collect();
+
+		// Fits a parabola through the peak and its neighbours to find the level between grid points
+		let peak_level = |index: usize| {
+			let (previous, peak, next) = (decibels[index - 1], decibels[index], decibels[index + 1]);
+			let curvature = previous - 2.0 * peak + next;
+			if curvature == 0.0 { peak } else { peak - (previous - next) * (previous - next) / (8.0 * curvature) }
+		};
+		let highest_sidelobe = peaks.iter().map(|index| peak_level(*index)).fold(f64::NEG_INFINITY, f64::max);
+
+		// Interpolates the magnitude between the grid points on either side of a level
+		let lobe_width = |level: f64| {
+			let level = magnitudes[0] * 10.0_f64.powf(level / 20.0);
+			let index = (1..=main_lobe_end).find(|index| magnitudes[*index] < level).unwrap_or(main_lobe_end);
+			let (previous, next) = (magnitudes[index - 1], magnitudes[index]);
+			let fraction = if previous == next { 0.0 } else { (previous - level) / (previous - next) };
+			2.0 * bin(index as f64 - 1.0 + fraction)
+		};
+
+		let nearest_peak = |target: f64| peaks.iter().cloned()
+			.min_by(|a, b| (bin(*a as f64) - target).abs().total_cmp(&(bin(*b as f64) - target).abs()));
+		let sidelobe_falloff = match (nearest_peak(width as f64 / 16.0), nearest_peak(width as f64 / 8.0)) {
+			(Some(low), Some(high)) if low != high =>
+				(peak_level(high) - peak_level(low)) / (high as f64 / low as f64).log2(),
+			_ => f64::NAN,
+		};
+
+		WindowMetrics {
+			coherent_gain: sum / width as f64,
+			equivalent_noise_bandwidth,
+			processing_gain: 1.0 / equivalent_noise_bandwidth,
+			scalloping_loss: -decibels[padding_factor / 2],
+			highest_sidelobe,
+			half_power_width: lobe_width(-10.0 * 2.0_f64.log10()),
+			half_amplitude_width: lobe_width(-20.0 * 2.0_f64.log10()),
+			sidelobe_falloff,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::fourier_transform::CorrelationFourier;
+	use crate::window::{Dirichlet, Hann};
+	use super::*;
+
+	fn assert_close(value: f64, expected: f64, tolerance: f64) {
+		assert!((value - expected).abs() < tolerance, "{} {}", value, expected);
+	}
+
+	#[test]
+	fn test_hann_metrics() {
+		let window: Window = Window::generate_periodic::<Hann>(256);
+		let metrics = window.metrics(&CorrelationFourier::new(256 * 8));
+		assert_close(metrics.coherent_gain, 0.5, 1e-12);
+		assert_close(metrics.equivalent_noise_bandwidth, 1.5, 1e-12);
+		assert_close(metrics.processing_gain, 1.0 / 1.5, 1e-12);
+		assert_close(metrics.scalloping_loss, 1.42, 0.01);
+		assert_close(metrics.highest_sidelobe, -31.5, 0.1);
+		assert_close(metrics.half_power_width, 1.44, 0.01);
+		assert_close(metrics.half_amplitude_width, 2.0, 0.01);
+		assert_close(metrics.sidelobe_falloff, -18.0, 0.5);
+	}
+
+	#[test]
+	fn test_dirichlet_metrics() {
+		let window: Window = Window::generate::<Dirichlet>(256);
+		let metrics = window.metrics(&CorrelationFourier::new(256 * 8));
+		assert_close(metrics.coherent_gain, 1.0, 1e-12);
+		assert_close(metrics.equivalent_noise_bandwidth, 1.0, 1e-12);
+		assert_close(metrics.processing_gain, 1.0, 1e-12);
+		assert_close(metrics.scalloping_loss, 3.92, 0.01);
+		assert_close(metrics.highest_sidelobe, -13.26, 0.05);
+		assert_close(metrics.half_power_width, 0.89, 0.01);
+		assert_close(metrics.half_amplitude_width, 1.21, 0.01);
+		assert_close(metrics.sidelobe_falloff, -6.0, 0.5);
+	}
+}
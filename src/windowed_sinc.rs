@@ -0,0 +1,172 @@
+use crate::float::Float;
+use crate::frequency_response;
+use crate::math;
+use crate::window::{ParametricWindow, Window};
+use super::{Hertz, SampleRate};
+
+/// Converts a frequency into a fraction of the sample rate
+pub fn normalized_frequency(frequency: Hertz, sample_rate: SampleRate) -> f64 {
+	frequency / sample_rate as f64
+}
+
+/// Kernel that passes frequencies below the cutoff with a gain of one at zero frequency
+/// Cutoffs are fractions of the sample rate and the kernel is centred on half its length
+pub fn low_pass<S, P>(window: &P, cutoff: f64, length: usize) -> Vec<S> where S: Float, P: ParametricWindow {
+	assert!(cutoff > 0.0 && cutoff < 0.5);
+	assert!(length > 1);
+	let center = (length - 1) as f64 / 2.0;
+	let kernel: Vec<S> = (0..length).map(|n| {
+		let offset = n as f64 - center;
+		S::convert(2.0 * cutoff * math::sinc(2.0 * cutoff * offset))
+	}).collect();
+
+	let kernel = Window::generate_parametric(window, length).apply(&kernel);
+	normalize_gain(&kernel, 0.0)
+}
+
+/// Kernel that passes frequencies above the cutoff with a gain of one at the Nyquist frequency
+/// The length must be odd
+pub fn high_pass<S, P>(window: &P, cutoff: f64, length: usize) -> Vec<S> where S: Float, P: ParametricWindow {
+	let kernel = spectral_inversion(&low_pass::<S, P>(window, cutoff, length));
+	normalize_gain(&kernel, 0.5)
+}
+
+/// Kernel that rejects frequencies between the two cutoffs
+/// The length must be odd
+pub fn band_stop<S, P>(window: &P, low_cutoff: f64, high_cutoff: f64, length: usize) -> Vec<S> where S: Float, P: ParametricWindow {
+	assert!(low_cutoff < high_cutoff);
+	let low_pass = low_pass::<S, P>(window, low_cutoff, length);
+	let high_pass = high_pass::<S, P>(window, high_cutoff, length);
+	low_pass.into_iter().zip(high_pass).map(|(low, high)| low + high).collect()
+}
+
+/// Kernel that passes frequencies between the two cutoffs with a gain of one at their centre
+/// The length must be odd
+pub fn band_pass<S, P>(window: &P, low_cutoff: f64, high_cutoff: f64, length: usize) -> Vec<S> where S: Float, P: ParametricWindow {
+	let kernel = spectral_inversion(&band_stop::<S, P>(window, low_cutoff, high_cutoff, length));
+	normalize_gain(&kernel, (low_cutoff + high_cutoff) / 2.0)
+}
+
+/// Flips the frequency response upside down by subtracting the kernel from an impulse
+/// The length must be odd so that the impulse is at the centre of symmetry
+pub fn spectral_inversion<S>(kernel: &[S]) -> Vec<S> where S: Float {
+	assert!(!kernel.len().is_multiple_of(2));
+	let mut kernel: Vec<_> = kernel.iter().map(|sample| -*sample).collect();
+	let center = kernel.len() / 2;
+	kernel[center] += S::one();
+	kernel
+}
+
+/// Flips the frequency response left to right so that a gain at a frequency
+/// is moved to one half minus the frequency
+pub fn spectral_reversal<S>(kernel: &[S]) -> Vec<S> where S: Float {
+	kernel.iter().enumerate()
+		.map(|(index, sample)| if index % 2 == 0 { *sample } else { -*sample })
+		.collect()
+}
+
+/// Magnitude of the frequency response at a single frequency
+pub fn frequency_gain<S>(kernel: &[S], frequency: f64) -> S where S: Float {
+	// A sample rate of one keeps the frequency as a fraction of the sample rate
+	frequency_response::kernel_response(kernel, 1, &[frequency]).magnitudes()[0]
+}
+
+/// Scales the kernel so that the gain at a frequency is one
+pub fn normalize_gain<S>(kernel: &[S], frequency: f64) -> Vec<S> where S: Float {
+	let gain = frequency_gain(kernel, frequency);
+	assert!(gain > S::zero());
+	kernel.iter().map(|sample| *sample / gain).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::convolution;
+	use crate::fourier_transform::{CorrelationFourier, FourierTransform};
+	use crate::window::{Hann, Kaiser, Sine};
+	use super::*;
+
+	fn assert_gain(kernel: &[f64], frequency: f64, expected: f64, tolerance: f64) {
+		let gain = frequency_gain(kernel, frequency);
+		assert!((gain - expected).abs() < tolerance, "{} {} {}", frequency, gain, expected);
+	}
+
+	#[test]
+	fn test_low_pass() {
+		let kernel = low_pass::<f64, _>(&Hann(), 0.1, 101);
+		assert!((kernel.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+		assert_gain(&kernel, 0.05, 1.0, 1e-3);
+		assert_gain(&kernel, 0.1, 0.5, 2e-2);
+		assert_gain(&kernel, 0.2, 0.0, 1e-3);
+		assert_gain(&kernel, 0.45, 0.0, 1e-3);
+		assert_eq!(kernel.iter().zip(kernel.iter().rev()).filter(|(a, b)| (*a - *b).abs() > 1e-15).count(), 0);
+	}
+
+	#[test]
+	fn test_high_pass() {
+		let kernel = high_pass::<f64, _>(&Hann(), 0.2, 101);
+		assert_gain(&kernel, 0.0, 0.0, 1e-3);
+		assert_gain(&kernel, 0.1, 0.0, 1e-3);
+		assert_gain(&kernel, 0.3, 1.0, 1e-3);
+		assert_gain(&kernel, 0.5, 1.0, 1e-12);
+
+		// Short kernels have a gain away from one at the Nyquist frequency before normalisation
+		let inverted = spectral_inversion(&low_pass::<f64, _>(&Hann(), 0.4, 11));
+		assert!((frequency_gain(&inverted, 0.5) - 1.0).abs() > 1e-3);
+		assert_gain(&high_pass::<f64, _>(&Hann(), 0.4, 11), 0.5, 1.0, 1e-12);
+	}
+
+	#[test]
+	fn test_band_filters() {
+		let kernel = band_pass::<f64, _>(&Sine(), 0.15, 0.3, 151);
+		assert_gain(&kernel, 0.225, 1.0, 1e-12);
+		assert_gain(&kernel, 0.2, 1.0, 1e-2);
+		assert_gain(&kernel, 0.05, 0.0, 1e-2);
+		assert_gain(&kernel, 0.45, 0.0, 1e-2);
+
+		let kernel = band_stop::<f64, _>(&Sine(), 0.15, 0.3, 151);
+		assert_gain(&kernel, 0.0, 1.0, 1e-2);
+		assert_gain(&kernel, 0.225, 0.0, 1e-2);
+		assert_gain(&kernel, 0.5, 1.0, 1e-2);
+	}
+
+	#[test]
+	fn test_parametric_window() {
+		let kernel = low_pass::<f64, _>(&Kaiser { beta: 8.0 }, 0.1, 101);
+		assert!((kernel.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+		assert_gain(&kernel, 0.05, 1.0, 1e-3);
+		assert_gain(&kernel, 0.2, 0.0, 1e-3);
+
+		let kernel = band_pass::<f64, _>(&Kaiser { beta: 6.0 }, 0.15, 0.3, 151);
+		assert_gain(&kernel, 0.225, 1.0, 1e-12);
+		assert_gain(&kernel, 0.05, 0.0, 1e-2);
+	}
+
+	#[test]
+	fn test_normalized_frequency() {
+		assert_eq!(normalized_frequency(1000.0, 8000), 0.125);
+	}
+
+	#[test]
+	fn test_spectral_reversal() {
+		let low_pass = low_pass::<f64, _>(&Hann(), 0.1, 51);
+		let reversed = spectral_reversal(&low_pass);
+		for frequency in [0.0, 0.07, 0.2, 0.33, 0.5].iter() {
+			assert_gain(&reversed, 0.5 - frequency, frequency_gain(&low_pass, *frequency), 1e-12);
+		}
+	}
+
+	#[test]
+	fn test_convolve_fourier() {
+		let signal: Vec<_> = (0..200).map(|x| {
+			let x = x as f64 * 2.0 * std::f64::consts::PI;
+			(x * 0.02).sin() + (x * 0.3).sin()
+		}).collect();
+		let kernel = low_pass::<f64, _>(&Hann(), 0.1, 61);
+		let transform = CorrelationFourier::new(signal.len() + kernel.len() - 1);
+		let output = convolution::convolve_fourier(&transform, &signal, &kernel);
+		for (index, sample) in output.iter().enumerate().skip(kernel.len()).take(signal.len() - kernel.len()) {
+			let expected = ((index - kernel.len() / 2) as f64 * 2.0 * std::f64::consts::PI * 0.02).sin();
+			assert!((sample - expected).abs() < 1e-2, "{} {} {}", index, sample, expected);
+		}
+	}
+}
@@ -0,0 +1,160 @@
+use crate::biquad::{Cascade, Coefficients, Form};
+use crate::float::Float;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Padding {
+	None,
+	/// Reflects the signal about the edge sample and inverts it which keeps the slope continuous
+	Odd,
+	/// Mirrors the signal about the edge sample
+	Even,
+	/// Repeats the edge sample
+	Constant,
+}
+
+/// Padding length used for a finite impulse response kernel
+pub fn kernel_padding_length(kernel_length: usize) -> usize {
+	3 * kernel_length
+}
+
+/// Padding length used for second order sections
+pub fn sections_padding_length(section_count: usize) -> usize {
+	3 * (2 * section_count + 1)
+}
+
+/// Extends both edges of the signal by at most one less than its length
+pub fn pad_signal<S>(signal: &[S], padding: Padding, length: usize) -> Vec<S> where S: Float {
+	let length = match padding {
+		Padding::None => 0,
+		_ => usize::min(length, signal.len().saturating_sub(1)),
+	};
+
+	let (first, last) = match (signal.first(), signal.last()) {
+		(Some(first), Some(last)) => (*first, *last),
+		_ => return Vec::new(),
+	};
+
+	let extend = |edge: S, sample: S| match padding {
+		Padding::Odd => edge + edge - sample,
+		Padding::Even => sample,
+		Padding::Constant | Padding::None => edge,
+	};
+
+	let start = signal[1..=length].iter().rev().map(|sample| extend(first, *sample));
+	let end_samples = &signal[signal.len() - 1 - length..signal.len() - 1];
+	let end = end_samples.iter().rev().map(|sample| extend(last, *sample));
+	start.chain(signal.iter().cloned()).chain(end).collect()
+}
+
+/// Filters a signal with a kernel forwards and backwards which squares the magnitude response
+/// Each pass starts from the state of a constant input equal to its first sample
+pub fn filtfilt_kernel<S>(kernel: &[S], signal: &[S], padding: Padding, padding_length: usize) -> Vec<S> where S: Float {
+	assert!(!kernel.is_empty());
+	filtfilt(signal, padding, padding_length, |signal| filter_kernel(kernel, signal))
+}
+
+/// Filters a signal with a cascade of second order sections forwards and backwards
+pub fn filtfilt_sections<S>(sections: &[Coefficients<S>], signal: &[S], padding: Padding,
+                            padding_length: usize) -> Vec<S> where S: Float {
+	let mut cascade = Cascade::new(sections, Form::TransposedDirectTwo);
+	filtfilt(signal, padding, padding_length, |signal| {
+		cascade.settle(signal[0]);
+		cascade.process(signal)
+	})
+}
+
+fn filtfilt<S, F>(signal: &[S], padding: Padding, padding_length: usize, mut filter: F)
+                  -> Vec<S> where S: Float, F: FnMut(&[S]) -> Vec<S> {
+	if signal.is_empty() {
+		return Vec::new();
+	}
+
+	let padded = pad_signal(signal, padding, padding_length);
+	let offset = (padded.len() - signal.len()) / 2;
+	let mut forward = filter(&padded);
+	forward.reverse();
+	let mut output = filter(&forward);
+	output.reverse();
+	output[offset..offset + signal.len()].to_vec()
+}
+
+/// Convolution where samples before the start are equal to the first sample
+fn filter_kernel<S>(kernel: &[S], signal: &[S]) -> Vec<S> where S: Float {
+	(0..signal.len()).map(|index| kernel.iter().enumerate().map(|(offset, coefficient)| {
+		*coefficient * signal[index.saturating_sub(offset)]
+	}).fold(S::zero(), |sum, sample| sum + sample)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::filter_kernels;
+	use crate::iir_design::{self, BandType, Prototype};
+	use super::*;
+
+	const PADDINGS: [Padding; 4] = [Padding::None, Padding::Odd, Padding::Even, Padding::Constant];
+
+	fn step(length: usize, start: usize) -> Vec<f64> {
+		(0..length).map(|index| if index < start { 0.0 } else { 1.0 }).collect()
+	}
+
+	/// Zero phase filters with unit gain have step responses that are antisymmetric about the step
+	fn assert_zero_delay(output: &[f64], start: usize, extent: usize) {
+		for offset in 0..extent {
+			let sum = output[start + offset] + output[start - 1 - offset];
+			assert!((sum - 1.0).abs() < 1e-6, "{} {}", offset, sum);
+		}
+	}
+
+	fn butterworth() -> Vec<Coefficients> {
+		iir_design::design(Prototype::Butterworth, 4, BandType::LowPass(400.0), 8000).second_order_sections()
+	}
+
+	#[test]
+	fn test_pad_signal() {
+		let signal = [1.0, 2.0, 4.0, 7.0];
+		assert_eq!(pad_signal(&signal, Padding::Odd, 2), vec![-2.0, 0.0, 1.0, 2.0, 4.0, 7.0, 10.0, 12.0]);
+		assert_eq!(pad_signal(&signal, Padding::Even, 2), vec![4.0, 2.0, 1.0, 2.0, 4.0, 7.0, 4.0, 2.0]);
+		assert_eq!(pad_signal(&signal, Padding::Constant, 2), vec![1.0, 1.0, 1.0, 2.0, 4.0, 7.0, 7.0, 7.0]);
+		assert_eq!(pad_signal(&signal, Padding::None, 2), signal.to_vec());
+		assert_eq!(pad_signal(&signal, Padding::Even, 10).len(), 10);
+		assert!(pad_signal::<f64>(&[], Padding::Odd, 3).is_empty());
+	}
+
+	#[test]
+	fn test_step_kernel() {
+		let kernel = filter_kernels::low_pass_square_pulse(1.0 / 9.0, 9);
+		let signal = step(200, 100);
+		for padding in PADDINGS.iter().cloned() {
+			let output = filtfilt_kernel(&kernel, &signal, padding, kernel_padding_length(kernel.len()));
+			assert_eq!(output.len(), signal.len());
+			assert_zero_delay(&output, 100, 90);
+		}
+	}
+
+	#[test]
+	fn test_step_sections() {
+		let sections = butterworth();
+		let signal = step(2000, 1000);
+		for padding in PADDINGS.iter().cloned() {
+			let output = filtfilt_sections(&sections, &signal, padding, sections_padding_length(sections.len()));
+			assert_zero_delay(&output, 1000, 500);
+		}
+	}
+
+	#[test]
+	fn test_edges() {
+		let sections = butterworth();
+		let constant = vec![3.0; 100];
+		for padding in PADDINGS.iter().cloned() {
+			let output = filtfilt_sections(&sections, &constant, padding, sections_padding_length(sections.len()));
+			output.iter().for_each(|sample| assert!((sample - 3.0).abs() < 1e-9));
+		}
+
+		// A symmetric kernel preserves a ramp when the odd extension continues it
+		let kernel = filter_kernels::low_pass_square_pulse(1.0 / 5.0, 5);
+		let ramp: Vec<_> = (0..50).map(|x| x as f64 * 0.5 - 3.0).collect();
+		let output = filtfilt_kernel(&kernel, &ramp, Padding::Odd, kernel_padding_length(kernel.len()));
+		output.iter().zip(ramp.iter()).for_each(|(sample, expected)| assert!((sample - expected).abs() < 1e-9));
+		assert!(filtfilt_kernel(&kernel, &[], Padding::Odd, 3).is_empty());
+	}
+}